@@ -23,7 +23,10 @@ impl Progress {
 }
 
 impl WithProgress for Progress {
-    fn setup(&mut self, size: usize) { self.interval = size / 10; }
+    fn setup(&mut self, size: Option<u64>) {
+        // Without a known size, print a dot every 100 KiB.
+        self.interval = size.map(|s| s as usize / 10).unwrap_or(100 * 1024);
+    }
 
     fn progress(&mut self, amount: usize) {
         self.amount += amount;
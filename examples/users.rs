@@ -24,7 +24,7 @@ fn main() {
     let token = Token::new(access_token, refresh_token);
 
     let client = ClientBuilder::new("centerdevice.de", client_credentials).build_with_token(token);
-    let users_query = UsersQuery { all: true };
+    let users_query = UsersQuery::new().all();
 
     let users = client.search_users(users_query).expect("Search failed.");
 
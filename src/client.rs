@@ -2,7 +2,12 @@ pub mod auth;
 pub mod collections;
 pub mod delete;
 pub mod download;
+pub mod folders;
+pub mod groups;
+pub mod metadata;
+pub mod saved_searches;
 pub mod search;
+pub mod sharing;
 pub mod upload;
 pub mod users;
 
@@ -10,11 +15,15 @@ pub use auth::{Code, CodeProvider, Token};
 
 use crate::{
     client::{
-        collections::{CollectionsQuery, CollectionsResult},
-        download::Download,
-        search::{Search, SearchResult},
+        collections::{Collection, CollectionOptions, CollectionsQuery, CollectionsResult},
+        download::{BulkDownload, BulkDownloadResult, Download},
+        folders::{Folder, FoldersResult},
+        groups::{Group, GroupsResult},
+        saved_searches::SavedSearchesResult,
+        search::{Search, SearchDocumentsIter, SearchResult},
+        sharing::{MembersResult, Permission, Principal, Shares},
         upload::Upload,
-        users::{UsersQuery, UsersResult},
+        users::{Invitation, User, UserRole, UsersQuery, UsersResult},
     },
     errors::{Error, ErrorKind, Result},
     CenterDevice,
@@ -24,6 +33,7 @@ use crate::{
 
 use failure::Fail;
 use reqwest::{self, blocking::Response, IntoUrl, StatusCode};
+use serde::{Deserialize, Serialize};
 
 pub struct UnauthorizedClient<'a> {
     pub(crate) base_url:           &'a str,
@@ -77,6 +87,28 @@ impl<'a> CenterDevice for AuthorizedClient<'a> {
 
     fn search_documents(&self, search: Search) -> Result<SearchResult> { search::search_documents(self, search) }
 
+    fn search_documents_iter<'b>(&'b self, search: Search<'b>) -> SearchDocumentsIter<'b, Self> {
+        search::search_documents_iter(self, search)
+    }
+
+    fn list_saved_searches(&self) -> Result<SavedSearchesResult> { saved_searches::list_saved_searches(self) }
+
+    fn create_saved_search(&self, name: &str, search: &Search) -> Result<ID> {
+        saved_searches::create_saved_search(self, name, search)
+    }
+
+    fn run_saved_search(&self, saved_search_id: &str) -> Result<SearchResult> {
+        saved_searches::run_saved_search(self, saved_search_id)
+    }
+
+    fn update_saved_search(&self, saved_search_id: &str, name: &str, search: &Search) -> Result<()> {
+        saved_searches::update_saved_search(self, saved_search_id, name, search)
+    }
+
+    fn delete_saved_search(&self, saved_search_id: &str) -> Result<()> {
+        saved_searches::delete_saved_search(self, saved_search_id)
+    }
+
     fn upload_file(&self, upload: Upload) -> Result<ID> { upload::upload_file(&self, upload) }
 
     fn download_file(&self, download: Download) -> Result<u64> { download::download_file(self, download) }
@@ -85,17 +117,173 @@ impl<'a> CenterDevice for AuthorizedClient<'a> {
         download::download_file_with_progress(self, download, progress)
     }
 
+    fn download_many(&self, bulk_download: BulkDownload) -> Result<BulkDownloadResult> {
+        download::download_many(self, bulk_download)
+    }
+
+    fn download_many_with_progress<T: WithProgress>(
+        &self,
+        bulk_download: BulkDownload,
+        progress: &mut T,
+    ) -> Result<BulkDownloadResult> {
+        download::download_many_with_progress(self, bulk_download, progress)
+    }
+
     fn delete_documents(&self, document_ids: &[&str]) -> Result<()> { delete::delete_documents(self, document_ids) }
 
+    fn update_extended_metadata<T: Serialize>(&self, document_id: &str, patch: &T) -> Result<()> {
+        metadata::update_extended_metadata(self, document_id, patch)
+    }
+
+    fn share_documents(&self, document_ids: &[&str], principals: &[Principal]) -> Result<()> {
+        sharing::share_documents(self, document_ids, principals)
+    }
+
+    fn unshare_documents(&self, document_ids: &[&str], principals: &[Principal]) -> Result<()> {
+        sharing::unshare_documents(self, document_ids, principals)
+    }
+
+    fn document_shares(&self, document_id: &str) -> Result<Shares> { sharing::document_shares(self, document_id) }
+
     fn search_users(&self, users_query: UsersQuery) -> Result<UsersResult> { users::search_users(self, users_query) }
 
+    fn get_user(&self, user_id: &str) -> Result<User> { users::get_user(self, user_id) }
+
+    fn current_user(&self) -> Result<User> { users::current_user(self) }
+
+    fn invite_user(&self, invitation: Invitation) -> Result<ID> { users::invite_user(self, invitation) }
+
+    fn resend_invitation(&self, user_id: &str) -> Result<()> { users::resend_invitation(self, user_id) }
+
+    fn block_user(&self, user_id: &str) -> Result<()> { users::block_user(self, user_id) }
+
+    fn unblock_user(&self, user_id: &str) -> Result<()> { users::unblock_user(self, user_id) }
+
+    fn change_user_role(&self, user_id: &str, role: UserRole) -> Result<()> {
+        users::change_user_role(self, user_id, role)
+    }
+
+    fn set_technical_user(&self, user_id: &str, technical_user: bool) -> Result<()> {
+        users::set_technical_user(self, user_id, technical_user)
+    }
+
+    fn list_groups(&self) -> Result<GroupsResult> { groups::list_groups(self) }
+
+    fn get_group(&self, group_id: &str) -> Result<Group> { groups::get_group(self, group_id) }
+
+    fn create_group(&self, name: &str, user_ids: &[&str]) -> Result<Group> {
+        groups::create_group(self, name, user_ids)
+    }
+
+    fn rename_group(&self, group_id: &str, name: &str) -> Result<()> { groups::rename_group(self, group_id, name) }
+
+    fn delete_group(&self, group_id: &str) -> Result<()> { groups::delete_group(self, group_id) }
+
+    fn add_group_members(&self, group_id: &str, user_ids: &[&str]) -> Result<()> {
+        groups::add_group_members(self, group_id, user_ids)
+    }
+
+    fn remove_group_members(&self, group_id: &str, user_ids: &[&str]) -> Result<()> {
+        groups::remove_group_members(self, group_id, user_ids)
+    }
+
     fn search_collections(&self, collections_query: CollectionsQuery) -> Result<CollectionsResult> {
         collections::search_collections(self, collections_query)
     }
+
+    fn collection_documents<'b>(
+        &'b self,
+        collection_id: &'b str,
+        folder_id: Option<&'b str>,
+    ) -> SearchDocumentsIter<'b, Self> {
+        collections::collection_documents(self, collection_id, folder_id)
+    }
+
+    fn get_collection(&self, collection_id: &str) -> Result<Collection> {
+        collections::get_collection(self, collection_id)
+    }
+
+    fn create_collection(&self, name: &str, options: CollectionOptions) -> Result<Collection> {
+        collections::create_collection(self, name, options)
+    }
+
+    fn rename_collection(&self, collection_id: &str, name: &str) -> Result<Collection> {
+        collections::rename_collection(self, collection_id, name)
+    }
+
+    fn archive_collection(&self, collection_id: &str) -> Result<Collection> {
+        collections::archive_collection(self, collection_id)
+    }
+
+    fn unarchive_collection(&self, collection_id: &str) -> Result<Collection> {
+        collections::unarchive_collection(self, collection_id)
+    }
+
+    fn set_collection_public(&self, collection_id: &str, public: bool) -> Result<Collection> {
+        collections::set_collection_public(self, collection_id, public)
+    }
+
+    fn set_collection_auditing(&self, collection_id: &str, auditing: bool) -> Result<Collection> {
+        collections::set_collection_auditing(self, collection_id, auditing)
+    }
+
+    fn delete_collection(&self, collection_id: &str) -> Result<()> {
+        collections::delete_collection(self, collection_id)
+    }
+
+    fn collection_members(&self, collection_id: &str) -> Result<MembersResult> {
+        sharing::collection_members(self, collection_id)
+    }
+
+    fn add_collection_member(&self, collection_id: &str, principal: Principal, permission: Permission) -> Result<()> {
+        sharing::add_collection_member(self, collection_id, principal, permission)
+    }
+
+    fn set_collection_member_permission(
+        &self,
+        collection_id: &str,
+        principal: Principal,
+        permission: Permission,
+    ) -> Result<()> {
+        sharing::set_collection_member_permission(self, collection_id, principal, permission)
+    }
+
+    fn remove_collection_member(&self, collection_id: &str, principal: Principal) -> Result<()> {
+        sharing::remove_collection_member(self, collection_id, principal)
+    }
+
+    fn folder_tree(&self, collection_id: &str) -> Result<FoldersResult> { folders::folder_tree(self, collection_id) }
+
+    fn get_folder(&self, folder_id: &str) -> Result<Folder> { folders::get_folder(self, folder_id) }
+
+    fn create_folder(&self, name: &str, collection_id: &str, parent_id: Option<&str>) -> Result<Folder> {
+        folders::create_folder(self, name, collection_id, parent_id)
+    }
+
+    fn rename_folder(&self, folder_id: &str, name: &str) -> Result<()> { folders::rename_folder(self, folder_id, name) }
+
+    fn move_folder(&self, folder_id: &str, parent_id: Option<&str>) -> Result<()> {
+        folders::move_folder(self, folder_id, parent_id)
+    }
+
+    fn delete_folder(&self, folder_id: &str) -> Result<()> { folders::delete_folder(self, folder_id) }
+
+    fn folder_documents<'b>(&'b self, folder_id: &'b str) -> SearchDocumentsIter<'b, Self> {
+        folders::folder_documents(self, folder_id)
+    }
+
+    fn move_documents(&self, document_ids: &[&str], source_folder_id: &str, target_folder_id: &str) -> Result<()> {
+        folders::move_documents(self, document_ids, source_folder_id, target_folder_id)
+    }
 }
 
 pub type ID = String;
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct Id {
+    pub(crate) id: ID,
+}
+
 pub(crate) trait GeneralErrHandler {
     type T: std::marker::Sized;
 
@@ -110,6 +298,7 @@ impl GeneralErrHandler for Response {
             code if expected_states.contains(&code) => Ok(self),
             code @ StatusCode::UNAUTHORIZED => Err(Error::from(ErrorKind::ApiCallFailedInvalidToken(code))),
             code @ StatusCode::TOO_MANY_REQUESTS => Err(Error::from(ErrorKind::ApiCallFailedTooManyRequests(code))),
+            code @ StatusCode::FORBIDDEN => Err(Error::from(ErrorKind::ApiCallFailedForbidden(code))),
             _ => Err(handle_error(self)),
         }
     }
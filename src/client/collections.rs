@@ -1,6 +1,14 @@
 use crate::{
-    client::{AuthorizedClient, GeneralErrHandler, ID},
+    client::{
+        self,
+        search::{self, Search, SearchDocumentsIter, SortDirection},
+        AuthorizedClient,
+        GeneralErrHandler,
+        ID,
+    },
     errors::{Error, ErrorKind, Result},
+    utils::serialize,
+    CenterDevice,
 };
 
 use chrono::{DateTime, FixedOffset};
@@ -10,25 +18,31 @@ use reqwest::{blocking::Response, StatusCode};
 use serde::{self, Deserialize, Serialize};
 use std::string::ToString;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Default)]
 pub struct CollectionsQuery<'a> {
     include_public: bool,
     name:           Option<&'a str>,
     ids:            Option<Vec<&'a str>>,
+    archived:       Option<bool>,
+    owner:          Option<&'a str>,
+    owned_by_me:    bool,
+    auditing:       Option<bool>,
+    has_folders:    Option<bool>,
+    sort:           Option<(CollectionSortField, SortDirection)>,
+    offset:         Option<usize>,
+    rows:           Option<usize>,
 }
 
-impl<'a> Default for CollectionsQuery<'a> {
-    fn default() -> Self {
+impl<'a> CollectionsQuery<'a> {
+    pub fn new() -> Self { Default::default() }
+
+    /// Queries the collections owned by the authorized user.
+    pub fn owned_by_me() -> Self {
         CollectionsQuery {
-            include_public: false,
-            name:           None,
-            ids:            None,
+            owned_by_me: true,
+            ..Default::default()
         }
     }
-}
-
-impl<'a> CollectionsQuery<'a> {
-    pub fn new() -> Self { Default::default() }
 
     pub fn include_public(self) -> CollectionsQuery<'a> {
         CollectionsQuery {
@@ -45,6 +59,105 @@ impl<'a> CollectionsQuery<'a> {
     }
 
     pub fn ids(self, ids: Vec<&'a str>) -> CollectionsQuery<'a> { CollectionsQuery { ids: Some(ids), ..self } }
+
+    /// Restricts the query to archived collections if `archived` is set, to active ones otherwise.
+    pub fn archived(self, archived: bool) -> CollectionsQuery<'a> {
+        CollectionsQuery {
+            archived: Some(archived),
+            ..self
+        }
+    }
+
+    pub fn owner(self, owner_id: &'a str) -> CollectionsQuery<'a> {
+        CollectionsQuery {
+            owner: Some(owner_id),
+            ..self
+        }
+    }
+
+    pub fn auditing(self, auditing: bool) -> CollectionsQuery<'a> {
+        CollectionsQuery {
+            auditing: Some(auditing),
+            ..self
+        }
+    }
+
+    pub fn has_folders(self, has_folders: bool) -> CollectionsQuery<'a> {
+        CollectionsQuery {
+            has_folders: Some(has_folders),
+            ..self
+        }
+    }
+
+    pub fn sort_by(self, field: CollectionSortField, direction: SortDirection) -> CollectionsQuery<'a> {
+        CollectionsQuery {
+            sort: Some((field, direction)),
+            ..self
+        }
+    }
+
+    /// Sets the number of collections to skip.
+    pub fn offset(self, offset: usize) -> CollectionsQuery<'a> {
+        CollectionsQuery {
+            offset: Some(offset),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of collections to return.
+    pub fn rows(self, rows: usize) -> CollectionsQuery<'a> {
+        CollectionsQuery {
+            rows: Some(rows),
+            ..self
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if self.include_public {
+            params.push(("include-public", "true".to_string()));
+        }
+        if let Some(name) = self.name {
+            params.push(("name", name.to_string()));
+        }
+        if let Some(ref ids) = self.ids {
+            params.push(("ids", ids.as_slice().join(",")));
+        }
+        if let Some(archived) = self.archived {
+            params.push(("archived", archived.to_string()));
+        }
+        if let Some(owner) = self.owner {
+            params.push(("owner", owner.to_string()));
+        }
+        if self.owned_by_me {
+            params.push(("owned-by-me", "true".to_string()));
+        }
+        if let Some(auditing) = self.auditing {
+            params.push(("auditing", auditing.to_string()));
+        }
+        if let Some(has_folders) = self.has_folders {
+            params.push(("has-folders", has_folders.to_string()));
+        }
+        if let Some((field, direction)) = self.sort {
+            params.push(("sort", serialize::variant_name(&field)));
+            params.push(("order", serialize::variant_name(&direction)));
+        }
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+        if let Some(rows) = self.rows {
+            params.push(("rows", rows.to_string()));
+        }
+        params
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CollectionSortField {
+    Name,
+    Owner,
+    ArchivedDate,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -73,24 +186,49 @@ pub struct Collection {
     pub has_folders:   Option<bool>,
 }
 
+/// Options for creating a new collection.
+#[derive(Serialize, Debug, Default)]
+pub struct CollectionOptions {
+    public:   bool,
+    auditing: bool,
+}
+
+impl CollectionOptions {
+    pub fn new() -> Self { Default::default() }
+
+    pub fn public(self) -> CollectionOptions { CollectionOptions { public: true, ..self } }
+
+    /// Enables the audit trail of the collection; note that auditing cannot be disabled once
+    /// enabled.
+    pub fn auditing(self) -> CollectionOptions { CollectionOptions { auditing: true, ..self } }
+}
+
+#[derive(Serialize, Debug)]
+struct CollectionDefinition<'a> {
+    name:    &'a str,
+    #[serde(flatten)]
+    options: CollectionOptions,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct CollectionUpdate<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name:     Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public:   Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auditing: Option<bool>,
+}
+
 pub fn search_collections(
     authorized_client: &AuthorizedClient,
     collection_query: CollectionsQuery,
 ) -> Result<CollectionsResult> {
     let url = format!("https://api.{}/v2/collections", authorized_client.base_url);
 
-    let ids_str; // Borrow checker
-    let mut params = Vec::new();
-    if collection_query.include_public {
-        params.push(("include-public", "true"));
-    }
-    if let Some(name) = collection_query.name {
-        params.push(("name", name));
-    }
-    if let Some(ids) = collection_query.ids {
-        ids_str = ids.as_slice().join(",");
-        params.push(("ids", &ids_str));
-    }
+    let params = collection_query.params();
 
     let request = authorized_client
         .http_client
@@ -125,3 +263,244 @@ pub fn search_collections(
 
     Ok(result)
 }
+
+pub fn get_collection(authorized_client: &AuthorizedClient, collection_id: &str) -> Result<Collection> {
+    let url = format!(
+        "https://api.{}/v2/collection/{}",
+        authorized_client.base_url, collection_id
+    );
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result)
+}
+
+/// Creates a new collection and returns it.
+pub fn create_collection(
+    authorized_client: &AuthorizedClient,
+    name: &str,
+    options: CollectionOptions,
+) -> Result<Collection> {
+    let url = format!("https://api.{}/v2/collections", authorized_client.base_url);
+
+    let definition = CollectionDefinition { name, options };
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&definition);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::CREATED])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result: client::Id = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    get_collection(authorized_client, &result.id)
+}
+
+pub fn rename_collection(authorized_client: &AuthorizedClient, collection_id: &str, name: &str) -> Result<Collection> {
+    let update = CollectionUpdate {
+        name: Some(name),
+        ..Default::default()
+    };
+    update_collection(authorized_client, collection_id, &update)
+}
+
+pub fn archive_collection(authorized_client: &AuthorizedClient, collection_id: &str) -> Result<Collection> {
+    let update = CollectionUpdate {
+        archived: Some(true),
+        ..Default::default()
+    };
+    update_collection(authorized_client, collection_id, &update)
+}
+
+pub fn unarchive_collection(authorized_client: &AuthorizedClient, collection_id: &str) -> Result<Collection> {
+    let update = CollectionUpdate {
+        archived: Some(false),
+        ..Default::default()
+    };
+    update_collection(authorized_client, collection_id, &update)
+}
+
+pub fn set_collection_public(
+    authorized_client: &AuthorizedClient,
+    collection_id: &str,
+    public: bool,
+) -> Result<Collection> {
+    let update = CollectionUpdate {
+        public: Some(public),
+        ..Default::default()
+    };
+    update_collection(authorized_client, collection_id, &update)
+}
+
+/// Enables or disables the audit trail; CenterDevice rejects disabling auditing once it has been
+/// enabled.
+pub fn set_collection_auditing(
+    authorized_client: &AuthorizedClient,
+    collection_id: &str,
+    auditing: bool,
+) -> Result<Collection> {
+    let update = CollectionUpdate {
+        auditing: Some(auditing),
+        ..Default::default()
+    };
+    update_collection(authorized_client, collection_id, &update)
+}
+
+/// Updates the collection and returns its new state.
+fn update_collection(
+    authorized_client: &AuthorizedClient,
+    collection_id: &str,
+    update: &CollectionUpdate,
+) -> Result<Collection> {
+    let url = format!(
+        "https://api.{}/v2/collection/{}",
+        authorized_client.base_url, collection_id
+    );
+
+    let request = authorized_client
+        .http_client
+        .put(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(update);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    get_collection(authorized_client, collection_id)
+}
+
+pub fn delete_collection(authorized_client: &AuthorizedClient, collection_id: &str) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/collection/{}",
+        authorized_client.base_url, collection_id
+    );
+
+    let request = authorized_client
+        .http_client
+        .delete(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+/// Iterates over all documents of a collection, or only those directly contained in a folder of the
+/// collection, fetching further pages as needed. A folder of another collection yields no
+/// documents.
+pub fn collection_documents<'a, C: CenterDevice>(
+    client: &'a C,
+    collection_id: &'a str,
+    folder_id: Option<&'a str>,
+) -> SearchDocumentsIter<'a, C> {
+    search::search_documents_iter(client, collection_search(collection_id, folder_id))
+}
+
+fn collection_search<'a>(collection_id: &'a str, folder_id: Option<&'a str>) -> Search<'a> {
+    match folder_id {
+        Some(folder_id) => Search::new().collections(vec![collection_id]).folder(folder_id, false),
+        None => Search::new().collection(collection_id),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn serialize_collection_definition() {
+        let definition = CollectionDefinition {
+            name:    "Scans",
+            options: CollectionOptions::new().auditing(),
+        };
+
+        let json = serde_json::to_value(&definition).expect("Failed to serialize");
+
+        assert_that(&json).is_equal_to(serde_json::json!({"name": "Scans", "public": false, "auditing": true}));
+    }
+
+    #[test]
+    fn collection_search_scope() {
+        let collection = serde_json::to_value(collection_search("c1", None)).expect("Failed to serialize");
+        let folder = serde_json::to_value(collection_search("c1", Some("f1"))).expect("Failed to serialize");
+
+        assert_that(&collection["scope"]).is_equal_to(serde_json::json!({"collection": "c1"}));
+        assert_that(&folder["scope"]).is_equal_to(serde_json::json!({"folder": {"id": "f1", "recursive": false}}));
+        assert_that(&folder["collections"]).is_equal_to(serde_json::json!(["c1"]));
+    }
+
+    #[test]
+    fn collections_query_params() {
+        let query = CollectionsQuery::owned_by_me()
+            .archived(false)
+            .has_folders(true)
+            .sort_by(CollectionSortField::Name, SortDirection::Descending)
+            .offset(100)
+            .rows(50);
+
+        let params = query.params();
+
+        assert_that(&params).is_equal_to(vec![
+            ("archived", "false".to_string()),
+            ("owned-by-me", "true".to_string()),
+            ("has-folders", "true".to_string()),
+            ("sort", "name".to_string()),
+            ("order", "desc".to_string()),
+            ("offset", "100".to_string()),
+            ("rows", "50".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn serialize_collection_update() {
+        let update = CollectionUpdate {
+            archived: Some(true),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&update).expect("Failed to serialize");
+
+        assert_that(&json).is_equal_to(serde_json::json!({"archived": true}));
+    }
+}
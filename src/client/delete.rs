@@ -30,9 +30,9 @@ impl<'a> DeleteAction<'a> {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-struct FailedDocuments {
+pub(crate) struct FailedDocuments {
     #[serde(rename = "failed-documents")]
-    failed_documents: Vec<ID>,
+    pub(crate) failed_documents: Vec<ID>,
 }
 
 pub fn delete_documents(authorized_client: &AuthorizedClient, document_ids: &[&str]) -> Result<()> {
@@ -1,24 +1,46 @@
 use crate::{
-    client::{AuthorizedClient, GeneralErrHandler},
+    client::{
+        search::{Document, SearchResult},
+        AuthorizedClient,
+        GeneralErrHandler,
+        ID,
+    },
     errors::{Error, ErrorKind, Result},
     WithProgress,
 };
 
+use crossbeam_utils::thread;
 use failure::Fail;
 use log::debug;
 use reqwest::{blocking::Response, header, StatusCode};
 use std::{
-    fs::File,
-    io::{BufWriter, Write},
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
     path::{Path, PathBuf},
     string::ToString,
+    sync::{mpsc, Mutex},
 };
 
+/// Determines what happens if the download's target file already exists.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CollisionPolicy {
+    /// Replace the existing file.
+    Overwrite,
+    /// Keep the existing file and do not download the document; the download reports 0 bytes.
+    Skip,
+    /// Choose a new name by appending a counter, e.g. `name (1).pdf`.
+    Rename,
+    /// Fail with `ErrorKind::FileAlreadyExists`.
+    Error,
+}
+
 #[derive(Debug)]
 pub struct Download<'a> {
-    document_id: &'a str,
-    dir:         &'a Path,
-    filename:    Option<&'a Path>,
+    document_id:      &'a str,
+    dir:              &'a Path,
+    filename:         Option<&'a Path>,
+    collision_policy: CollisionPolicy,
+    hash:             Option<&'a str>,
 }
 
 impl<'a> Download<'a> {
@@ -27,16 +49,124 @@ impl<'a> Download<'a> {
             document_id,
             dir,
             filename: None,
+            collision_policy: CollisionPolicy::Overwrite,
+            hash: None,
         }
     }
 
+    /// Creates a download for a document from a search result that is verified against the
+    /// document's hash.
+    pub fn from_document(document: &'a Document, dir: &'a Path) -> Download<'a> {
+        Download::new(&document.id, dir).hash(&document.hash)
+    }
+
+    /// Sets the filename to store the document under. This filename is used verbatim; filenames
+    /// sent by the server are sanitized.
     pub fn filename(self, filename: &'a Path) -> Download<'a> {
         Download {
             filename: Some(filename),
             ..self
         }
     }
+
+    pub fn collision_policy(self, collision_policy: CollisionPolicy) -> Download<'a> {
+        Download {
+            collision_policy,
+            ..self
+        }
+    }
+
+    /// Sets the expected MD5 hash of the document as hex string, cf. `Document::hash`. The download
+    /// fails with `ErrorKind::DownloadChecksumMismatch` if the downloaded content does not match.
+    pub fn hash(self, hash: &'a str) -> Download<'a> {
+        Download {
+            hash: Some(hash),
+            ..self
+        }
+    }
+}
+
+/// Downloads many documents into one directory concurrently.
+#[derive(Debug)]
+pub struct BulkDownload<'a> {
+    downloads:        Vec<Download<'a>>,
+    collision_policy: CollisionPolicy,
+    workers:          usize,
+}
+
+impl<'a> BulkDownload<'a> {
+    pub fn new(document_ids: &[&'a str], dir: &'a Path) -> BulkDownload<'a> {
+        let downloads = document_ids.iter().map(|id| Download::new(id, dir)).collect();
+        BulkDownload::from_downloads(downloads)
+    }
+
+    /// Creates a bulk download for all documents of a search result; each download is verified
+    /// against the document's hash.
+    pub fn from_search_result(search_result: &'a SearchResult, dir: &'a Path) -> BulkDownload<'a> {
+        let downloads = search_result
+            .documents
+            .iter()
+            .map(|d| Download::from_document(d, dir))
+            .collect();
+        BulkDownload::from_downloads(downloads)
+    }
+
+    fn from_downloads(downloads: Vec<Download<'a>>) -> BulkDownload<'a> {
+        BulkDownload {
+            downloads,
+            collision_policy: CollisionPolicy::Rename,
+            workers: 4,
+        }
+    }
+
+    /// Sets the collision policy for all downloads; defaults to `CollisionPolicy::Rename` so that
+    /// documents with the same filename do not overwrite each other.
+    pub fn collision_policy(self, collision_policy: CollisionPolicy) -> BulkDownload<'a> {
+        BulkDownload {
+            collision_policy,
+            ..self
+        }
+    }
+
+    /// Sets the number of concurrent downloads; defaults to 4.
+    pub fn workers(self, workers: usize) -> BulkDownload<'a> {
+        BulkDownload {
+            workers: workers.max(1),
+            ..self
+        }
+    }
+}
+
+/// The outcome of a bulk download per document in the order of the requested documents.
+#[derive(Debug)]
+pub struct BulkDownloadResult {
+    pub results: Vec<(ID, Result<u64>)>,
+}
+
+impl BulkDownloadResult {
+    pub fn is_ok(&self) -> bool { self.results.iter().all(|(_, r)| r.is_ok()) }
+
+    pub fn failed(&self) -> impl Iterator<Item = (&ID, &Error)> {
+        self.results
+            .iter()
+            .filter_map(|(id, r)| r.as_ref().err().map(|e| (id, e)))
+    }
 }
+
+/// Forwards the progress of a single download of a bulk download to the thread driving the
+/// aggregated progress.
+struct ChannelProgress {
+    sender: mpsc::Sender<usize>,
+}
+
+impl WithProgress for ChannelProgress {
+    fn setup(&mut self, _: Option<u64>) {}
+
+    fn progress(&mut self, amount: usize) { let _ = self.sender.send(amount); }
+
+    fn finish(&self) {}
+}
+
 struct ProgressWriter<'a, P: ?Sized, W> {
     progress: Option<&'a mut P>,
     inner:    W,
@@ -54,6 +184,97 @@ impl<'a, P: WithProgress + ?Sized, W: Write> Write for ProgressWriter<'a, P, W>
     fn flush(&mut self) -> std::io::Result<()> { self.inner.flush() }
 }
 
+struct DigestWriter<W> {
+    digest: md5::Context,
+    inner:  W,
+}
+
+impl<W: Write> Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let amount = self.inner.write(buf)?;
+        self.digest.consume(&buf[..amount]);
+        Ok(amount)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> { self.inner.flush() }
+}
+
+/// A partially downloaded file next to its final destination. It gets removed when dropped unless
+/// it has been persisted.
+struct PartialFile {
+    path:      PathBuf,
+    persisted: bool,
+}
+
+impl PartialFile {
+    fn new(file_path: &Path, document_id: &str) -> PartialFile {
+        let filename = file_path.file_name().map(|f| f.to_string_lossy()).unwrap_or_default();
+        let path = file_path.with_file_name(format!(".{}.{}.part", filename, document_id));
+        PartialFile { path, persisted: false }
+    }
+
+    /// Moves the file to `file_path`, replacing an existing file.
+    fn persist(mut self, file_path: &Path) -> io::Result<()> {
+        fs::rename(&self.path, file_path)?;
+        self.persisted = true;
+        Ok(())
+    }
+
+    /// Moves the file to `file_path` unless that exists, in which case it fails with
+    /// `io::ErrorKind::AlreadyExists`. Existing files are never replaced, even by concurrent
+    /// downloads, because hard links fail atomically if the target exists. On file systems without
+    /// hard links, the target is claimed by creating it exclusively and then replaced.
+    fn persist_no_replace(&mut self, file_path: &Path) -> io::Result<()> {
+        match fs::hard_link(&self.path, file_path) {
+            Ok(()) => {
+                let _ = fs::remove_file(&self.path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(e),
+            Err(_) => {
+                OpenOptions::new().write(true).create_new(true).open(file_path)?;
+                if let Err(e) = fs::rename(&self.path, file_path) {
+                    let _ = fs::remove_file(file_path);
+                    return Err(e);
+                }
+            }
+        }
+        self.persisted = true;
+        Ok(())
+    }
+
+    /// Moves the file to `file_path` or, if that exists, to the first free `name (n).ext` next to
+    /// it and returns the chosen path. Existing files are never replaced.
+    fn persist_new(mut self, file_path: &Path) -> io::Result<PathBuf> {
+        let stem = file_path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        let extension = file_path.extension().map(|e| e.to_string_lossy());
+
+        let mut candidate = file_path.to_path_buf();
+        for n in 1.. {
+            match self.persist_no_replace(&candidate) {
+                Ok(()) => return Ok(candidate),
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let filename = match extension {
+                        Some(ref ext) => format!("{} ({}).{}", stem, n, ext),
+                        None => format!("{} ({})", stem, n),
+                    };
+                    candidate = file_path.with_file_name(filename);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        unreachable!("Exhausted all candidate filenames")
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
 pub fn download_file(authorized_client: &AuthorizedClient, download: Download) -> Result<u64> {
     do_download(authorized_client, download, None::<&mut dyn WithProgress>)
 }
@@ -66,6 +287,87 @@ pub fn download_file_with_progress<T: WithProgress + ?Sized>(
     do_download(authorized_client, download, Some(progress))
 }
 
+pub fn download_many(authorized_client: &AuthorizedClient, bulk_download: BulkDownload) -> Result<BulkDownloadResult> {
+    do_download_many(authorized_client, bulk_download, None::<&mut dyn WithProgress>)
+}
+
+/// Downloads many documents and reports their combined progress. Since the total size is not known
+/// in advance, `WithProgress::setup` receives `None`.
+pub fn download_many_with_progress<T: WithProgress + ?Sized>(
+    authorized_client: &AuthorizedClient,
+    bulk_download: BulkDownload,
+    progress: &mut T,
+) -> Result<BulkDownloadResult> {
+    do_download_many(authorized_client, bulk_download, Some(progress))
+}
+
+fn do_download_many<T: WithProgress + ?Sized>(
+    authorized_client: &AuthorizedClient,
+    bulk_download: BulkDownload,
+    mut progress: Option<&mut T>,
+) -> Result<BulkDownloadResult> {
+    let collision_policy = bulk_download.collision_policy;
+    let ids: Vec<ID> = bulk_download
+        .downloads
+        .iter()
+        .map(|d| d.document_id.to_string())
+        .collect();
+    let workers = bulk_download.workers.min(ids.len());
+    let jobs = Mutex::new(bulk_download.downloads.into_iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(ids.len()));
+
+    if let Some(ref mut p) = progress {
+        p.setup(None);
+    }
+
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for i in 0..workers {
+            let (jobs, results) = (&jobs, &results);
+            let mut channel_progress = ChannelProgress { sender: sender.clone() };
+            scope
+                .builder()
+                .name(format!("download-{}", i))
+                .spawn(move |_| {
+                    loop {
+                        let job = jobs.lock().expect("Download jobs lock poisoned").next();
+                        let (index, download) = match job {
+                            Some(job) => job,
+                            None => break,
+                        };
+                        let download = download.collision_policy(collision_policy);
+                        let result = do_download(authorized_client, download, Some(&mut channel_progress));
+                        results
+                            .lock()
+                            .expect("Download results lock poisoned")
+                            .push((index, result));
+                    }
+                })
+                .map_err(|e| e.context(ErrorKind::FailedToSpawnWorker))?;
+        }
+        drop(sender);
+
+        for amount in receiver {
+            if let Some(ref mut p) = progress {
+                p.progress(amount);
+            }
+        }
+
+        Ok::<_, Error>(())
+    })
+    .expect("Download worker panicked")?;
+
+    if let Some(ref mut p) = progress {
+        p.finish();
+    }
+
+    let mut results = results.into_inner().expect("Download results lock poisoned");
+    results.sort_by_key(|(index, _)| *index);
+    let results = ids.into_iter().zip(results.into_iter().map(|(_, r)| r)).collect();
+
+    Ok(BulkDownloadResult { results })
+}
+
 fn do_download<T: WithProgress + ?Sized>(
     authorized_client: &AuthorizedClient,
     download: Download,
@@ -94,14 +396,28 @@ fn do_download<T: WithProgress + ?Sized>(
         PathBuf::from(f_path)
     } else {
         let f_content_disposition = get_filename(&response)?;
-        PathBuf::from(f_content_disposition)
+        let sanitized = sanitize_filename(&f_content_disposition).unwrap_or_else(|| download.document_id.to_string());
+        PathBuf::from(sanitized)
     };
     debug!("Filename: {:#?}", filename);
 
     let mut file_path = PathBuf::from(&download.dir);
     file_path.push(filename);
 
-    let file = File::create(file_path.as_path()).map_err(|e| {
+    let rename = download.collision_policy == CollisionPolicy::Rename;
+    if !rename && file_path.exists() {
+        match download.collision_policy {
+            CollisionPolicy::Skip => {
+                debug!("Skipping download, because file '{}' exists", file_path.display());
+                return Ok(0);
+            }
+            CollisionPolicy::Error => return Err(Error::from(ErrorKind::FileAlreadyExists(file_path))),
+            CollisionPolicy::Overwrite | CollisionPolicy::Rename => {}
+        }
+    }
+
+    let mut partial_file = PartialFile::new(&file_path, download.document_id);
+    let file = File::create(&partial_file.path).map_err(|e| {
         e.context(ErrorKind::FailedToProcessHttpResponse(
             status_code,
             "creating file".to_string(),
@@ -110,9 +426,12 @@ fn do_download<T: WithProgress + ?Sized>(
 
     let mut writer = {
         if let Some(ref mut p) = progress {
-            p.setup(content_length as usize);
+            p.setup(content_length);
         }
-        let inner = BufWriter::new(file);
+        let inner = DigestWriter {
+            digest: md5::Context::new(),
+            inner:  BufWriter::new(file),
+        };
         ProgressWriter { progress, inner }
     };
 
@@ -122,9 +441,61 @@ fn do_download<T: WithProgress + ?Sized>(
             "reading body".to_string(),
         ))
     })?;
-    assert_eq!(content_length, len);
+    match content_length {
+        Some(expected) if expected != len => {
+            return Err(Error::from(ErrorKind::DownloadSizeMismatch(expected, len)));
+        }
+        Some(_) => {}
+        None => debug!("Downloaded {} bytes without content length", len),
+    }
+
+    let ProgressWriter { mut progress, inner } = writer;
+    let DigestWriter { digest, inner } = inner;
+    inner
+        .into_inner()
+        .map_err(io::Error::from)
+        .and_then(|file| file.sync_all())
+        .map_err(|e| {
+            e.context(ErrorKind::FailedToProcessHttpResponse(
+                status_code,
+                "writing file".to_string(),
+            ))
+        })?;
+
+    if let Some(expected) = download.hash {
+        let actual = format!("{:x}", digest.compute());
+        if !expected.eq_ignore_ascii_case(&actual) {
+            return Err(Error::from(ErrorKind::DownloadChecksumMismatch(
+                expected.to_string(),
+                actual,
+            )));
+        }
+    }
+
+    let persisted = match download.collision_policy {
+        CollisionPolicy::Overwrite => partial_file.persist(&file_path),
+        CollisionPolicy::Rename => partial_file.persist_new(&file_path).map(|path| file_path = path),
+        CollisionPolicy::Skip | CollisionPolicy::Error => partial_file.persist_no_replace(&file_path),
+    };
+    match persisted {
+        Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            if download.collision_policy == CollisionPolicy::Skip {
+                debug!("Skipping download, because file '{}' exists", file_path.display());
+                return Ok(0);
+            }
+            return Err(Error::from(ErrorKind::FileAlreadyExists(file_path)));
+        }
+        persisted => persisted,
+    }
+    .map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status_code,
+            "moving file into place".to_string(),
+        ))
+    })?;
+    debug!("File path: {:#?}", file_path);
 
-    if let Some(ref mut p) = writer.progress {
+    if let Some(ref mut p) = progress {
         p.finish();
     }
 
@@ -132,9 +503,6 @@ fn do_download<T: WithProgress + ?Sized>(
 }
 
 fn get_filename(response: &Response) -> Result<String> {
-    // TODO: Upgrade to another version of mime_multifrom or replace because it uses hyper 0.10
-    // headers and mime 0.2
-    use hyperx::header::{ContentDisposition, DispositionParam, Header};
     use std::str;
 
     let status_code = response.status();
@@ -143,42 +511,159 @@ fn get_filename(response: &Response) -> Result<String> {
         .headers()
         .get(header::CONTENT_DISPOSITION)
         .ok_or_else(|| ErrorKind::FailedToProcessHttpResponse(status_code, "content disposition header".to_string()))?;
-    let content_disposition: ContentDisposition = ContentDisposition::parse_header(&header).map_err(|e| {
+    let content_disposition = str::from_utf8(header.as_bytes()).map_err(|e| {
         e.context(ErrorKind::FailedToProcessHttpResponse(
             status_code,
             "parsing content disposition header".to_string(),
         ))
     })?;
 
+    parse_filename(content_disposition).ok_or_else(|| {
+        Error::from(ErrorKind::FailedToProcessHttpResponse(
+            status_code,
+            "content disposition header filename not found".to_string(),
+        ))
+    })
+}
+
+/// Extracts the filename from a Content-Disposition header value. An RFC 5987 encoded `filename*`
+/// parameter takes precedence over a plain `filename` parameter; `filename*` values that cannot be
+/// decoded are ignored.
+fn parse_filename(content_disposition: &str) -> Option<String> {
+    // TODO: Upgrade to another version of mime_multifrom or replace because it uses hyper 0.10
+    // headers and mime 0.2
+    // hyperx' ContentDisposition does not tell `filename` and `filename*` apart, so we only use its
+    // ext-value parser.
+    use hyperx::header::{parsing::parse_extended_value, Charset};
+
     let mut filename = None;
-    for cp in &content_disposition.parameters {
-        if let DispositionParam::Filename(_, _, ref f) = *cp {
-            let decoded = str::from_utf8(f).map_err(|e| {
-                e.context(ErrorKind::FailedToProcessHttpResponse(
-                    status_code,
-                    "parsing content disposition filename".to_string(),
-                ))
-            })?;
-            filename = Some(decoded);
+    let mut filename_ext = None;
+    for (key, value) in header_params(content_disposition) {
+        if key.eq_ignore_ascii_case("filename") {
+            filename = Some(value);
+        } else if key.eq_ignore_ascii_case("filename*") {
+            filename_ext = parse_extended_value(&value).ok().and_then(|ext| {
+                match ext.charset {
+                    Charset::Ext(ref charset) if charset.eq_ignore_ascii_case("utf-8") => {
+                        String::from_utf8(ext.value).ok()
+                    }
+                    Charset::Us_Ascii => String::from_utf8(ext.value).ok(),
+                    Charset::Iso_8859_1 => Some(ext.value.iter().map(|&b| b as char).collect()),
+                    _ => None,
+                }
+            });
+        }
+    }
+
+    filename_ext.or(filename)
+}
+
+/// Splits the parameters of a header value like `attachment; filename="a; b.pdf"` into key-value
+/// pairs. `;` and `=` inside quoted strings do not separate parameters, and quoted-pairs such as
+/// `\"` are unescaped.
+fn header_params(header: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut chars = header.chars().peekable();
+
+    // Skip the disposition type
+    for c in &mut chars {
+        if c == ';' {
             break;
         }
     }
-    filename
-        .ok_or_else(|| {
-            Error::from(ErrorKind::FailedToProcessHttpResponse(
-                status_code,
-                "content disposition header filename not found".to_string(),
-            ))
-        })
-        .map(ToString::to_string)
+
+    while chars.peek().is_some() {
+        let mut key = String::new();
+        let mut has_value = false;
+        for c in &mut chars {
+            match c {
+                '=' => {
+                    has_value = true;
+                    break;
+                }
+                ';' => break,
+                c => key.push(c),
+            }
+        }
+        if !has_value {
+            continue;
+        }
+
+        while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+            chars.next();
+        }
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+            // Skip anything between the closing quote and the next parameter
+            for c in &mut chars {
+                if c == ';' {
+                    break;
+                }
+            }
+        } else {
+            for c in &mut chars {
+                if c == ';' {
+                    break;
+                }
+                value.push(c);
+            }
+            value = value.trim().to_string();
+        }
+
+        params.push((key.trim().to_string(), value));
+    }
+
+    params
 }
 
-fn get_content_length(response: &Response) -> Result<u64> {
+/// Turns a server-provided filename into a name that is safe to use inside the download directory:
+/// path components and control characters are removed, characters reserved on common file systems
+/// are replaced, and device names reserved on Windows are prefixed. Returns `None` if nothing
+/// usable remains.
+fn sanitize_filename(filename: &str) -> Option<String> {
+    const RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+    const RESERVED_NAMES: &[&str] = &[
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+        "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+
+    let basename = filename.rsplit(&['/', '\\'][..]).next().unwrap_or_default();
+    let cleaned: String = basename
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| if RESERVED_CHARS.contains(&c) { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.');
+
+    if cleaned.is_empty() {
+        return None;
+    }
+
+    let stem = cleaned.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|name| name.eq_ignore_ascii_case(stem)) {
+        Some(format!("_{}", cleaned))
+    } else {
+        Some(cleaned.to_string())
+    }
+}
+
+/// Returns `None` if the server did not send a content length, e.g., for chunked transfer encoding.
+fn get_content_length(response: &Response) -> Result<Option<u64>> {
     let status_code = response.status();
-    let content_length = response
-        .headers()
-        .get(header::CONTENT_LENGTH)
-        .ok_or_else(|| ErrorKind::FailedToProcessHttpResponse(status_code, "content length header".to_string()))?
+    let header = match response.headers().get(header::CONTENT_LENGTH) {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let content_length = header
         .to_str()
         .map_err(|e| {
             e.context(ErrorKind::FailedToProcessHttpResponse(
@@ -193,5 +678,181 @@ fn get_content_length(response: &Response) -> Result<u64> {
                 "parsing content length".to_string(),
             ))
         })?;
-    Ok(content_length)
+    Ok(Some(content_length))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    mod parse_filename {
+        use super::*;
+
+        #[test]
+        fn plain_filename() {
+            let filename = parse_filename(r#"attachment; filename="Branchenkatalog.pdf""#);
+
+            assert_that(&filename).is_equal_to(Some("Branchenkatalog.pdf".to_string()));
+        }
+
+        #[test]
+        fn prefers_extended_filename() {
+            let filename = parse_filename(
+                r#"attachment; filename="Gruesse.pdf"; filename*=UTF-8''Gr%C3%BC%C3%9Fe%20aus%20K%C3%B6ln.pdf"#,
+            );
+
+            assert_that(&filename).is_equal_to(Some("Grüße aus Köln.pdf".to_string()));
+        }
+
+        #[test]
+        fn latin1_extended_filename() {
+            let filename = parse_filename("attachment; filename*=ISO-8859-1''%DCbersicht.pdf");
+
+            assert_that(&filename).is_equal_to(Some("Übersicht.pdf".to_string()));
+        }
+
+        #[test]
+        fn invalid_extended_filename_falls_back() {
+            let filename = parse_filename("attachment; filename*=UTF-8''%FF%FE.pdf; filename=fallback.pdf");
+
+            assert_that(&filename).is_equal_to(Some("fallback.pdf".to_string()));
+        }
+
+        #[test]
+        fn quoted_separator() {
+            let filename = parse_filename(r#"attachment; filename="Angebot; final.pdf"; size=42"#);
+
+            assert_that(&filename).is_equal_to(Some("Angebot; final.pdf".to_string()));
+        }
+
+        #[test]
+        fn escaped_quotes() {
+            let filename = parse_filename(r#"attachment; filename="Das \"neue\" Angebot.pdf""#);
+
+            assert_that(&filename).is_equal_to(Some(r#"Das "neue" Angebot.pdf"#.to_string()));
+        }
+
+        #[test]
+        fn missing_filename() {
+            let filename = parse_filename("attachment");
+
+            assert_that(&filename).is_none();
+        }
+    }
+
+    mod digest_writer {
+        use super::*;
+
+        #[test]
+        fn computes_md5() {
+            let mut writer = DigestWriter {
+                digest: md5::Context::new(),
+                inner:  Vec::new(),
+            };
+
+            writer.write_all(b"CenterDevice").expect("Failed to write");
+            let digest = format!("{:x}", writer.digest.compute());
+
+            assert_that(&writer.inner).is_equal_to(b"CenterDevice".to_vec());
+            assert_that(&digest.as_str()).is_equal_to("b8b42e828f04962c8555f2b4050c2d6e");
+        }
+    }
+
+    mod partial_file {
+        use super::*;
+
+        #[test]
+        fn removed_unless_persisted() {
+            let file_path = std::env::temp_dir().join("centerdevice-partial-file-test.pdf");
+            let partial_file = PartialFile::new(&file_path, "0176fc13");
+            File::create(&partial_file.path).expect("Failed to create partial file");
+            let partial_path = partial_file.path.clone();
+
+            drop(partial_file);
+
+            assert_that(&partial_path.exists()).is_false();
+            assert_that(&file_path.exists()).is_false();
+        }
+
+        #[test]
+        fn persist_new_numbers_existing_files() {
+            let dir = std::env::temp_dir().join("centerdevice-persist-new-test");
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).expect("Failed to create test dir");
+            let path = dir.join("Branchenkatalog.pdf");
+
+            let persisted: Vec<PathBuf> = ["d1", "d2", "d3"]
+                .iter()
+                .map(|id| {
+                    let partial_file = PartialFile::new(&path, id);
+                    fs::write(&partial_file.path, id).expect("Failed to write partial file");
+                    partial_file.persist_new(&path).expect("Failed to persist file")
+                })
+                .collect();
+
+            assert_that(&persisted).is_equal_to(vec![
+                path.clone(),
+                dir.join("Branchenkatalog (1).pdf"),
+                dir.join("Branchenkatalog (2).pdf"),
+            ]);
+            assert_that(&fs::read_to_string(&path).expect("Failed to read file")).is_equal_to("d1".to_string());
+            assert_that(&fs::read_dir(&dir).expect("Failed to list test dir").count()).is_equal_to(3);
+
+            fs::remove_dir_all(&dir).expect("Failed to remove test dir");
+        }
+
+        #[test]
+        fn persist_no_replace_keeps_existing_file() {
+            let dir = std::env::temp_dir().join("centerdevice-persist-no-replace-test");
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).expect("Failed to create test dir");
+            let path = dir.join("Branchenkatalog.pdf");
+            fs::write(&path, "d1").expect("Failed to write file");
+
+            let mut partial_file = PartialFile::new(&path, "d2");
+            fs::write(&partial_file.path, "d2").expect("Failed to write partial file");
+            let persisted = partial_file.persist_no_replace(&path).map_err(|e| e.kind());
+            drop(partial_file);
+
+            assert_that(&persisted).is_equal_to(Err(io::ErrorKind::AlreadyExists));
+            assert_that(&fs::read_to_string(&path).expect("Failed to read file")).is_equal_to("d1".to_string());
+            assert_that(&fs::read_dir(&dir).expect("Failed to list test dir").count()).is_equal_to(1);
+
+            fs::remove_dir_all(&dir).expect("Failed to remove test dir");
+        }
+    }
+
+    mod sanitize_filename {
+        use super::*;
+
+        #[test]
+        fn keeps_regular_names() {
+            assert_that(&sanitize_filename("Grüße aus Köln.pdf")).is_equal_to(Some("Grüße aus Köln.pdf".to_string()));
+        }
+
+        #[test]
+        fn strips_path_components() {
+            assert_that(&sanitize_filename("../../.bashrc")).is_equal_to(Some(".bashrc".to_string()));
+            assert_that(&sanitize_filename("/etc/passwd")).is_equal_to(Some("passwd".to_string()));
+            assert_that(&sanitize_filename("..\\..\\boot.ini")).is_equal_to(Some("boot.ini".to_string()));
+        }
+
+        #[test]
+        fn removes_control_and_reserved_characters() {
+            assert_that(&sanitize_filename("re\u{0}port\n?.pdf")).is_equal_to(Some("report_.pdf".to_string()));
+        }
+
+        #[test]
+        fn prefixes_reserved_names() {
+            assert_that(&sanitize_filename("con.txt")).is_equal_to(Some("_con.txt".to_string()));
+        }
+
+        #[test]
+        fn rejects_empty_names() {
+            assert_that(&sanitize_filename("")).is_none();
+            assert_that(&sanitize_filename("..")).is_none();
+            assert_that(&sanitize_filename("foo/")).is_none();
+        }
+    }
 }
@@ -0,0 +1,360 @@
+use crate::{
+    client::{
+        self,
+        search::{self, Search, SearchDocumentsIter},
+        AuthorizedClient,
+        GeneralErrHandler,
+        ID,
+    },
+    errors::{Error, ErrorKind, Result},
+    CenterDevice,
+};
+
+use failure::Fail;
+use log::debug;
+use reqwest::{blocking::Response, StatusCode};
+use serde::{self, Deserialize, Serialize};
+use std::string::ToString;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct FoldersResult {
+    pub folders: Vec<Folder>,
+}
+
+impl FoldersResult {
+    /// Walks all folders depth-first, parents before their subfolders.
+    pub fn walk(&self) -> FolderWalk<'_> { FolderWalk::new(&self.folders) }
+
+    /// Finds a folder anywhere in the tree.
+    pub fn find(&self, folder_id: &str) -> Option<&Folder> { self.walk().find(|f| f.id == folder_id) }
+}
+
+impl Default for FoldersResult {
+    fn default() -> FoldersResult {
+        FoldersResult {
+            folders: Vec::with_capacity(0),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Folder {
+    pub id:         ID,
+    pub name:       String,
+    pub collection: ID,
+    pub parent:     Option<ID>,
+    #[serde(default)]
+    pub subfolders: Vec<Folder>,
+}
+
+impl Folder {
+    /// Walks this folder and all its subfolders depth-first, parents before their subfolders.
+    pub fn walk(&self) -> FolderWalk<'_> { FolderWalk::new(std::slice::from_ref(self)) }
+}
+
+/// Depth-first iterator over a folder tree.
+pub struct FolderWalk<'a> {
+    stack: Vec<&'a Folder>,
+}
+
+impl<'a> FolderWalk<'a> {
+    fn new(folders: &'a [Folder]) -> Self {
+        FolderWalk {
+            stack: folders.iter().rev().collect(),
+        }
+    }
+}
+
+impl<'a> Iterator for FolderWalk<'a> {
+    type Item = &'a Folder;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let folder = self.stack.pop()?;
+        self.stack.extend(folder.subfolders.iter().rev());
+        Some(folder)
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct FolderDefinition<'a> {
+    name:       &'a str,
+    collection: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent:     Option<&'a str>,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct FolderUpdate<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name:   Option<&'a str>,
+    /// `Some(None)` moves the folder to the top level of its collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<Option<&'a str>>,
+}
+
+#[derive(Serialize, Debug)]
+struct MoveDocumentsAction<'a> {
+    action: &'a str,
+    params: MoveDocuments<'a>,
+}
+
+#[derive(Serialize, Debug)]
+struct MoveDocuments<'a> {
+    documents:     &'a [&'a str],
+    #[serde(rename = "source-folder")]
+    source_folder: &'a str,
+}
+
+/// Lists the complete folder tree of a collection.
+pub fn folder_tree(authorized_client: &AuthorizedClient, collection_id: &str) -> Result<FoldersResult> {
+    let url = format!("https://api.{}/v2/folders", authorized_client.base_url);
+
+    let params = [("collection", collection_id), ("tree", "true")];
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .query(&params)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK, StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    let result = match response.status() {
+        status @ StatusCode::OK => {
+            response.json().map_err(|e| {
+                e.context(ErrorKind::FailedToProcessHttpResponse(
+                    status,
+                    "reading body".to_string(),
+                ))
+            })?
+        }
+        StatusCode::NO_CONTENT => FoldersResult::default(),
+        code => {
+            return Err(Error::from(ErrorKind::ApiCallFailed(
+                code,
+                "unexpected response code".to_string(),
+            )))
+        }
+    };
+
+    Ok(result)
+}
+
+pub fn get_folder(authorized_client: &AuthorizedClient, folder_id: &str) -> Result<Folder> {
+    let url = format!("https://api.{}/v2/folder/{}", authorized_client.base_url, folder_id);
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result)
+}
+
+/// Creates a folder in a collection, at the top level if `parent_id` is `None`, and returns it.
+pub fn create_folder(
+    authorized_client: &AuthorizedClient,
+    name: &str,
+    collection_id: &str,
+    parent_id: Option<&str>,
+) -> Result<Folder> {
+    let url = format!("https://api.{}/v2/folders", authorized_client.base_url);
+
+    let definition = FolderDefinition {
+        name,
+        collection: collection_id,
+        parent: parent_id,
+    };
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&definition);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::CREATED])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result: client::Id = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    get_folder(authorized_client, &result.id)
+}
+
+pub fn rename_folder(authorized_client: &AuthorizedClient, folder_id: &str, name: &str) -> Result<()> {
+    let update = FolderUpdate {
+        name: Some(name),
+        ..Default::default()
+    };
+    update_folder(authorized_client, folder_id, &update)
+}
+
+/// Moves a folder below another folder of the same collection, or to the top level if `parent_id`
+/// is `None`.
+pub fn move_folder(authorized_client: &AuthorizedClient, folder_id: &str, parent_id: Option<&str>) -> Result<()> {
+    let update = FolderUpdate {
+        parent: Some(parent_id),
+        ..Default::default()
+    };
+    update_folder(authorized_client, folder_id, &update)
+}
+
+fn update_folder(authorized_client: &AuthorizedClient, folder_id: &str, update: &FolderUpdate) -> Result<()> {
+    let url = format!("https://api.{}/v2/folder/{}", authorized_client.base_url, folder_id);
+
+    let request = authorized_client
+        .http_client
+        .put(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(update);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+/// Deletes a folder including its subfolders; the documents remain in the collection.
+pub fn delete_folder(authorized_client: &AuthorizedClient, folder_id: &str) -> Result<()> {
+    let url = format!("https://api.{}/v2/folder/{}", authorized_client.base_url, folder_id);
+
+    let request = authorized_client
+        .http_client
+        .delete(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+/// Iterates over the documents directly contained in a folder, fetching further pages as needed.
+pub fn folder_documents<'a, C: CenterDevice>(client: &'a C, folder_id: &'a str) -> SearchDocumentsIter<'a, C> {
+    search::search_documents_iter(client, Search::new().folder(folder_id, false))
+}
+
+pub fn move_documents(
+    authorized_client: &AuthorizedClient,
+    document_ids: &[&str],
+    source_folder_id: &str,
+    target_folder_id: &str,
+) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/folder/{}/documents",
+        authorized_client.base_url, target_folder_id
+    );
+
+    let move_action = MoveDocumentsAction {
+        action: "move",
+        params: MoveDocuments {
+            documents:     document_ids,
+            source_folder: source_folder_id,
+        },
+    };
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&move_action);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    static FOLDER_TREE_JSON: &str = r#"{
+    "folders": [
+        {
+            "id": "f1",
+            "name": "Verträge",
+            "collection": "c1",
+            "parent": null,
+            "subfolders": [
+                {
+                    "id": "f1.1",
+                    "name": "2018",
+                    "collection": "c1",
+                    "parent": "f1",
+                    "subfolders": [
+                        { "id": "f1.1.1", "name": "Q1", "collection": "c1", "parent": "f1.1" }
+                    ]
+                },
+                { "id": "f1.2", "name": "2019", "collection": "c1", "parent": "f1" }
+            ]
+        },
+        { "id": "f2", "name": "Rechnungen", "collection": "c1", "parent": null }
+    ]
+}"#;
+
+    #[test]
+    fn walk_folder_tree() {
+        let result: FoldersResult = serde_json::from_str(FOLDER_TREE_JSON).expect("Failed to deserialize");
+
+        let ids: Vec<&str> = result.walk().map(|f| f.id.as_str()).collect();
+
+        assert_that(&ids).is_equal_to(vec!["f1", "f1.1", "f1.1.1", "f1.2", "f2"]);
+        assert_that(&result.find("f1.1.1").map(|f| f.name.as_str())).is_equal_to(Some("Q1"));
+        assert_that(&result.find("f3")).is_none();
+    }
+
+    #[test]
+    fn serialize_move_to_top_level() {
+        let update = FolderUpdate {
+            parent: Some(None),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&update).expect("Failed to serialize");
+
+        assert_that(&json).is_equal_to(serde_json::json!({ "parent": null }));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    client::{self, AuthorizedClient, GeneralErrHandler, ID},
+    errors::{Error, ErrorKind, Result},
+};
+
+use failure::Fail;
+use log::debug;
+use reqwest::{blocking::Response, StatusCode};
+use serde::{self, Deserialize, Serialize};
+use std::string::ToString;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupsResult {
+    pub groups: Vec<Group>,
+}
+
+impl Default for GroupsResult {
+    fn default() -> GroupsResult {
+        GroupsResult {
+            groups: Vec::with_capacity(0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Group {
+    pub id:      ID,
+    pub name:    String,
+    /// Ids of the users in the group.
+    #[serde(default)]
+    pub members: Vec<ID>,
+}
+
+#[derive(Serialize, Debug)]
+struct GroupDefinition<'a> {
+    name:    &'a str,
+    members: &'a [&'a str],
+}
+
+#[derive(Serialize, Debug)]
+struct GroupUpdate<'a> {
+    name: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct MembersAction<'a> {
+    action: &'a str,
+    params: Members<'a>,
+}
+
+#[derive(Serialize, Debug)]
+struct Members<'a> {
+    users: &'a [&'a str],
+}
+
+pub fn list_groups(authorized_client: &AuthorizedClient) -> Result<GroupsResult> {
+    let url = format!("https://api.{}/v2/groups", authorized_client.base_url);
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK, StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    let result = match response.status() {
+        status @ StatusCode::OK => {
+            response.json().map_err(|e| {
+                e.context(ErrorKind::FailedToProcessHttpResponse(
+                    status,
+                    "reading body".to_string(),
+                ))
+            })?
+        }
+        StatusCode::NO_CONTENT => GroupsResult::default(),
+        code => {
+            return Err(Error::from(ErrorKind::ApiCallFailed(
+                code,
+                "unexpected response code".to_string(),
+            )))
+        }
+    };
+
+    Ok(result)
+}
+
+pub fn get_group(authorized_client: &AuthorizedClient, group_id: &str) -> Result<Group> {
+    let url = format!("https://api.{}/v2/group/{}", authorized_client.base_url, group_id);
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result)
+}
+
+/// Creates a group with the given users as members and returns it.
+pub fn create_group(authorized_client: &AuthorizedClient, name: &str, user_ids: &[&str]) -> Result<Group> {
+    let url = format!("https://api.{}/v2/groups", authorized_client.base_url);
+
+    let definition = GroupDefinition {
+        name,
+        members: user_ids,
+    };
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&definition);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::CREATED])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result: client::Id = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    get_group(authorized_client, &result.id)
+}
+
+pub fn rename_group(authorized_client: &AuthorizedClient, group_id: &str, name: &str) -> Result<()> {
+    let url = format!("https://api.{}/v2/group/{}", authorized_client.base_url, group_id);
+
+    let request = authorized_client
+        .http_client
+        .put(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&GroupUpdate { name });
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+pub fn delete_group(authorized_client: &AuthorizedClient, group_id: &str) -> Result<()> {
+    let url = format!("https://api.{}/v2/group/{}", authorized_client.base_url, group_id);
+
+    let request = authorized_client
+        .http_client
+        .delete(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+pub fn add_group_members(authorized_client: &AuthorizedClient, group_id: &str, user_ids: &[&str]) -> Result<()> {
+    group_members_action(authorized_client, group_id, "add-users", user_ids)
+}
+
+pub fn remove_group_members(authorized_client: &AuthorizedClient, group_id: &str, user_ids: &[&str]) -> Result<()> {
+    group_members_action(authorized_client, group_id, "remove-users", user_ids)
+}
+
+fn group_members_action(
+    authorized_client: &AuthorizedClient,
+    group_id: &str,
+    action: &str,
+    user_ids: &[&str],
+) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/group/{}/members",
+        authorized_client.base_url, group_id
+    );
+
+    let members_action = MembersAction {
+        action,
+        params: Members { users: user_ids },
+    };
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&members_action);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    static GROUPS_JSON: &str = r#"{
+    "groups": [
+        { "id": "g1", "name": "Buchhaltung", "members": [ "u1", "u2" ] },
+        { "id": "g2", "name": "Vertrieb" }
+    ]
+}"#;
+
+    #[test]
+    fn deserialize_groups() {
+        let result: GroupsResult = serde_json::from_str(GROUPS_JSON).expect("Failed to deserialize");
+
+        assert_that(&result.groups).has_length(2);
+        assert_that(&result.groups[0].members).is_equal_to(vec!["u1".to_string(), "u2".to_string()]);
+        assert_that(&result.groups[1].members).is_empty();
+    }
+
+    #[test]
+    fn serialize_members_action() {
+        let members_action = MembersAction {
+            action: "add-users",
+            params: Members { users: &["u1"] },
+        };
+
+        let json = serde_json::to_value(&members_action).expect("Failed to serialize");
+
+        assert_that(&json).is_equal_to(serde_json::json!({"action": "add-users", "params": {"users": ["u1"]}}));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    client::{AuthorizedClient, GeneralErrHandler},
+    errors::{ErrorKind, Result},
+};
+
+use failure::Fail;
+use log::debug;
+use reqwest::StatusCode;
+use serde::Serialize;
+
+/// Updates individual keys of a document's extended metadata. `patch` must serialize to a JSON
+/// object; keys with `null` values are removed, all other keys are set.
+pub fn update_extended_metadata<T: Serialize>(
+    authorized_client: &AuthorizedClient,
+    document_id: &str,
+    patch: &T,
+) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/document/{}/extended-metadata",
+        authorized_client.base_url, document_id
+    );
+
+    let patch = serde_json::to_value(patch)
+        .map_err(|e| e.context(ErrorKind::FailedToProcessExtendedMetadata("serializing".to_string())))?;
+    if !patch.is_object() {
+        return Err(ErrorKind::FailedToProcessExtendedMetadata("not a JSON object".to_string()).into());
+    }
+
+    let request = authorized_client
+        .http_client
+        .patch(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&patch);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use crate::{
+    client::{
+        self,
+        search::{self, internal, Search, SearchResult},
+        AuthorizedClient,
+        GeneralErrHandler,
+        ID,
+    },
+    errors::{Error, ErrorKind, Result},
+};
+
+use failure::Fail;
+use log::debug;
+use reqwest::{blocking::Response, StatusCode};
+use serde::{self, Deserialize, Serialize};
+use std::string::ToString;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedSearchesResult {
+    #[serde(rename = "saved-searches")]
+    pub saved_searches: Vec<SavedSearch>,
+}
+
+impl Default for SavedSearchesResult {
+    fn default() -> SavedSearchesResult {
+        SavedSearchesResult {
+            saved_searches: Vec::with_capacity(0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id:     ID,
+    pub name:   String,
+    /// The search in the API's request format. It is kept as is, because searches saved in the web
+    /// UI may use parameters that `Search` cannot express.
+    pub search: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Debug)]
+struct SavedSearchDefinition<'a> {
+    name:   &'a str,
+    search: internal::Params<'a>,
+}
+
+impl<'a> SavedSearchDefinition<'a> {
+    fn new(name: &'a str, search: &Search<'a>) -> Self {
+        let search = internal::Search::from_search(search.clone()).params;
+        SavedSearchDefinition { name, search }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct SavedSearchRequest<'a> {
+    action: &'a str,
+    params: &'a serde_json::Map<String, serde_json::Value>,
+}
+
+pub fn list_saved_searches(authorized_client: &AuthorizedClient) -> Result<SavedSearchesResult> {
+    let url = format!("https://api.{}/v2/searches", authorized_client.base_url);
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK, StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    let result = match response.status() {
+        status @ StatusCode::OK => {
+            response.json().map_err(|e| {
+                e.context(ErrorKind::FailedToProcessHttpResponse(
+                    status,
+                    "reading body".to_string(),
+                ))
+            })?
+        }
+        StatusCode::NO_CONTENT => SavedSearchesResult::default(),
+        code => {
+            return Err(Error::from(ErrorKind::ApiCallFailed(
+                code,
+                "unexpected response code".to_string(),
+            )))
+        }
+    };
+
+    Ok(result)
+}
+
+pub fn create_saved_search(authorized_client: &AuthorizedClient, name: &str, search: &Search) -> Result<ID> {
+    let url = format!("https://api.{}/v2/searches", authorized_client.base_url);
+
+    let definition = SavedSearchDefinition::new(name, search);
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&definition);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::CREATED])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result: client::Id = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result.id)
+}
+
+/// Fetches the saved search and runs it; fails if the saved search has no valid definition.
+pub fn run_saved_search(authorized_client: &AuthorizedClient, saved_search_id: &str) -> Result<SearchResult> {
+    let url = format!(
+        "https://api.{}/v2/search/{}",
+        authorized_client.base_url, saved_search_id
+    );
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let saved_search: SavedSearch = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    let request = SavedSearchRequest {
+        action: "search",
+        params: &saved_search.search,
+    };
+    search::post_search(authorized_client, &request)
+}
+
+pub fn update_saved_search(
+    authorized_client: &AuthorizedClient,
+    saved_search_id: &str,
+    name: &str,
+    search: &Search,
+) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/search/{}",
+        authorized_client.base_url, saved_search_id
+    );
+
+    let definition = SavedSearchDefinition::new(name, search);
+
+    let request = authorized_client
+        .http_client
+        .put(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&definition);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+pub fn delete_saved_search(authorized_client: &AuthorizedClient, saved_search_id: &str) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/search/{}",
+        authorized_client.base_url, saved_search_id
+    );
+
+    let request = authorized_client
+        .http_client
+        .delete(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn serialize_definition_in_request_format() {
+        let search = Search::new()
+            .tags(vec!["A"])
+            .named_searches(search::NamedSearch::MyDocuments);
+
+        let json = serde_json::to_value(SavedSearchDefinition::new("Meine A", &search)).expect("Failed to serialize");
+
+        assert_that(&json["search"]["filter"]["tags"]).is_equal_to(serde_json::json!(["A"]));
+        assert_that(&json["search"]["named"][0]["name"]).is_equal_to(serde_json::json!("my-documents"));
+        assert_that(&json["search"].get("named-search")).is_none();
+    }
+
+    #[test]
+    fn reject_saved_search_without_definition() {
+        let saved_search = serde_json::from_str::<SavedSearch>(r#"{ "id": "s1", "name": "Kaputt" }"#);
+        let invalid_search =
+            serde_json::from_str::<SavedSearch>(r#"{ "id": "s1", "name": "Kaputt", "search": "tags:A" }"#);
+
+        assert_that(&saved_search).is_err();
+        assert_that(&invalid_search).is_err();
+    }
+}
@@ -1,97 +1,492 @@
+pub mod query;
+
 use crate::{
-    client::{AuthorizedClient, GeneralErrHandler, ID},
-    errors::{ErrorKind, Result},
+    client::{search::query::Query, AuthorizedClient, GeneralErrHandler, ID},
+    errors::{Error, ErrorKind, Result},
     utils::{deserialize, serialize},
+    CenterDevice,
 };
 
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, Utc};
 use failure::Fail;
 use log::debug;
 use reqwest::{blocking::Response, StatusCode};
-use serde::{self, Deserialize, Serialize};
-use std::fmt;
+use serde::{self, de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, VecDeque},
+    convert::TryFrom,
+    fmt,
+};
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum NamedSearch {
     None,
     PublicCollections,
+    MyDocuments,
+    SharedWithMe,
+    RecentlyViewed,
+}
+
+impl NamedSearch {
+    fn name(&self) -> Option<&'static str> {
+        match *self {
+            NamedSearch::None => None,
+            NamedSearch::PublicCollections => Some("public-collections"),
+            NamedSearch::MyDocuments => Some("my-documents"),
+            NamedSearch::SharedWithMe => Some("shared-with-me"),
+            NamedSearch::RecentlyViewed => Some("recently-viewed"),
+        }
+    }
+}
+
+/// A document attribute to count the documents of a search result by.
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Facet {
+    Tags,
+    MimeTypes,
+    Owners,
+    Collections,
+    /// A facet this crate does not know yet, e.g., `languages`.
+    Other(String),
+}
+
+impl From<String> for Facet {
+    fn from(facet: String) -> Self {
+        match facet.as_str() {
+            "tags" => Facet::Tags,
+            "mimetypes" => Facet::MimeTypes,
+            "owners" => Facet::Owners,
+            "collections" => Facet::Collections,
+            _ => Facet::Other(facet),
+        }
+    }
+}
+
+impl From<Facet> for String {
+    fn from(facet: Facet) -> Self {
+        match facet {
+            Facet::Tags => "tags".to_string(),
+            Facet::MimeTypes => "mimetypes".to_string(),
+            Facet::Owners => "owners".to_string(),
+            Facet::Collections => "collections".to_string(),
+            Facet::Other(facet) => facet,
+        }
+    }
+}
+
+/// Requests highlighted snippets of the fulltext matches, cf. `SearchResult::highlights`. Unset
+/// values use the server's defaults.
+#[derive(PartialEq, Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct Highlight {
+    /// Maximum number of snippets per document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragments:     Option<usize>,
+    /// Approximate length of each snippet in characters.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "fragment-size")]
+    pub fragment_size: Option<usize>,
+}
+
+/// Restricts a search to the documents of a single collection or folder.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scope<'a> {
+    Collection(Cow<'a, str>),
+    Folder { id: Cow<'a, str>, recursive: bool },
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortField {
+    UploadDate,
+    DocumentDate,
+    VersionDate,
+    Filename,
+    Title,
+    Size,
+    Score,
 }
 
-#[derive(Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum SortDirection {
+    #[serde(rename = "asc")]
+    Ascending,
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+/// An inclusive range that may be open on either side. Ranges whose start is after their end cannot
+/// be constructed or deserialized.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    try_from = "Bounds<T>",
+    bound(deserialize = "T: Deserialize<'de> + PartialOrd + fmt::Debug")
+)]
+pub struct Range<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to:   Option<T>,
+}
+
+impl<T: PartialOrd + fmt::Debug> Range<T> {
+    /// Fails with `ErrorKind::InvalidRange` if `from` is after `to`.
+    pub fn between(from: T, to: T) -> Result<Range<T>> {
+        if from > to {
+            return Err(Error::from(ErrorKind::InvalidRange(format!("{:?} > {:?}", from, to))));
+        }
+        Ok(Range {
+            from: Some(from),
+            to:   Some(to),
+        })
+    }
+
+    pub fn since(from: T) -> Range<T> {
+        Range {
+            from: Some(from),
+            to:   None,
+        }
+    }
+
+    pub fn until(to: T) -> Range<T> {
+        Range {
+            from: None,
+            to:   Some(to),
+        }
+    }
+}
+
+impl<T> Range<T> {
+    pub fn from(&self) -> Option<&T> { self.from.as_ref() }
+
+    pub fn to(&self) -> Option<&T> { self.to.as_ref() }
+}
+
+#[derive(Deserialize)]
+struct Bounds<T> {
+    from: Option<T>,
+    to:   Option<T>,
+}
+
+impl<T: PartialOrd + fmt::Debug> TryFrom<Bounds<T>> for Range<T> {
+    type Error = ErrorKind;
+
+    fn try_from(bounds: Bounds<T>) -> ::std::result::Result<Self, Self::Error> {
+        match bounds {
+            Bounds {
+                from: Some(from),
+                to: Some(to),
+            } => Range::between(from, to).map_err(|e| e.kind().clone()),
+            Bounds { from, to } => Ok(Range { from, to }),
+        }
+    }
+}
+
+/// A document search. It can be serialized to store it locally; `Search<'static>` can be
+/// deserialized.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct Search<'a> {
-    filenames:    Option<Vec<&'a str>>,
-    tags:         Option<Vec<&'a str>>,
-    fulltext:     Option<&'a str>,
-    named_search: NamedSearch,
+    filenames:     Option<Vec<Cow<'a, str>>>,
+    tags:          Option<Vec<Cow<'a, str>>>,
+    fulltext:      Option<Cow<'a, str>>,
+    query:         Option<Query<'a>>,
+    named_search:  NamedSearch,
+    scope:         Option<Scope<'a>>,
+    offset:        Option<usize>,
+    rows:          Option<usize>,
+    sort:          Vec<(SortField, SortDirection)>,
+    facets:        Vec<Facet>,
+    highlight:     Option<Highlight>,
+    upload_date:   Option<Range<DateTime<Utc>>>,
+    document_date: Option<Range<DateTime<Utc>>>,
+    version_date:  Option<Range<DateTime<Utc>>>,
+    #[serde(
+        serialize_with = "serialize::option_mime_types",
+        deserialize_with = "deserialize::option_mime_types"
+    )]
+    mime_types:    Option<Vec<mime::Mime>>,
+    owners:        Option<Vec<Cow<'a, str>>>,
+    uploaders:     Option<Vec<Cow<'a, str>>>,
+    authors:       Option<Vec<Cow<'a, str>>>,
+    size:          Option<Range<u64>>,
+    collections:   Option<Vec<Cow<'a, str>>>,
+    metadata:      BTreeMap<Cow<'a, str>, serde_json::Value>,
 }
 
 impl<'a> Search<'a> {
     pub fn new() -> Search<'a> {
         Search {
-            filenames:    None,
-            tags:         None,
-            fulltext:     None,
-            named_search: NamedSearch::None,
+            filenames:     None,
+            tags:          None,
+            fulltext:      None,
+            query:         None,
+            named_search:  NamedSearch::None,
+            scope:         None,
+            offset:        None,
+            rows:          None,
+            sort:          Vec::new(),
+            facets:        Vec::new(),
+            highlight:     None,
+            upload_date:   None,
+            document_date: None,
+            version_date:  None,
+            mime_types:    None,
+            owners:        None,
+            uploaders:     None,
+            authors:       None,
+            size:          None,
+            collections:   None,
+            metadata:      BTreeMap::new(),
         }
     }
 
     pub fn filenames(self, filenames: Vec<&'a str>) -> Search<'a> {
         Search {
-            filenames: Some(filenames),
+            filenames: Some(borrowed(filenames)),
             ..self
         }
     }
 
     pub fn tags(self, tags: Vec<&'a str>) -> Search<'a> {
         Search {
-            tags: Some(tags),
+            tags: Some(borrowed(tags)),
             ..self
         }
     }
 
     pub fn fulltext(self, fulltext: &'a str) -> Search<'a> {
         Search {
-            fulltext: Some(fulltext),
+            fulltext: Some(Cow::Borrowed(fulltext)),
+            ..self
+        }
+    }
+
+    /// Sets a structured query; if a fulltext query is set, too, documents must match both.
+    pub fn query(self, query: Query<'a>) -> Search<'a> {
+        Search {
+            query: Some(query),
             ..self
         }
     }
 
     pub fn named_searches(self, named_search: NamedSearch) -> Search<'a> { Search { named_search, ..self } }
+
+    pub fn scope(self, scope: Scope<'a>) -> Search<'a> {
+        Search {
+            scope: Some(scope),
+            ..self
+        }
+    }
+
+    pub fn collection(self, collection_id: &'a str) -> Search<'a> {
+        self.scope(Scope::Collection(Cow::Borrowed(collection_id)))
+    }
+
+    /// Restricts the search to a folder and, if `recursive` is set, all its subfolders.
+    pub fn folder(self, folder_id: &'a str, recursive: bool) -> Search<'a> {
+        self.scope(Scope::Folder {
+            id: Cow::Borrowed(folder_id),
+            recursive,
+        })
+    }
+
+    /// Sets the number of documents to skip.
+    pub fn offset(self, offset: usize) -> Search<'a> {
+        Search {
+            offset: Some(offset),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of documents per result page.
+    pub fn rows(self, rows: usize) -> Search<'a> {
+        Search {
+            rows: Some(rows),
+            ..self
+        }
+    }
+
+    /// Adds a sort key; documents are sorted by the keys in the order they have been added.
+    pub fn sort_by(self, field: SortField, direction: SortDirection) -> Search<'a> {
+        let mut sort = self.sort;
+        sort.push((field, direction));
+        Search { sort, ..self }
+    }
+
+    /// Requests document counts per value of the given facets, cf. `SearchResult::facets`.
+    pub fn facets(self, facets: Vec<Facet>) -> Search<'a> { Search { facets, ..self } }
+
+    pub fn highlight(self, highlight: Highlight) -> Search<'a> {
+        Search {
+            highlight: Some(highlight),
+            ..self
+        }
+    }
+
+    pub fn upload_date(self, upload_date: Range<DateTime<Utc>>) -> Search<'a> {
+        Search {
+            upload_date: Some(upload_date),
+            ..self
+        }
+    }
+
+    pub fn document_date(self, document_date: Range<DateTime<Utc>>) -> Search<'a> {
+        Search {
+            document_date: Some(document_date),
+            ..self
+        }
+    }
+
+    pub fn version_date(self, version_date: Range<DateTime<Utc>>) -> Search<'a> {
+        Search {
+            version_date: Some(version_date),
+            ..self
+        }
+    }
+
+    pub fn mime_types(self, mime_types: Vec<mime::Mime>) -> Search<'a> {
+        Search {
+            mime_types: Some(mime_types),
+            ..self
+        }
+    }
+
+    pub fn owners(self, owners: Vec<&'a str>) -> Search<'a> {
+        Search {
+            owners: Some(borrowed(owners)),
+            ..self
+        }
+    }
+
+    pub fn uploaders(self, uploaders: Vec<&'a str>) -> Search<'a> {
+        Search {
+            uploaders: Some(borrowed(uploaders)),
+            ..self
+        }
+    }
+
+    pub fn authors(self, authors: Vec<&'a str>) -> Search<'a> {
+        Search {
+            authors: Some(borrowed(authors)),
+            ..self
+        }
+    }
+
+    /// Restricts the search to documents with a size in bytes within `size`.
+    pub fn size(self, size: Range<u64>) -> Search<'a> {
+        Search {
+            size: Some(size),
+            ..self
+        }
+    }
+
+    /// Restricts the search to documents in any of the given collections.
+    pub fn collections(self, collections: Vec<&'a str>) -> Search<'a> {
+        Search {
+            collections: Some(borrowed(collections)),
+            ..self
+        }
+    }
+
+    /// Restricts the search to documents whose extended metadata has `value` for `key`; may be
+    /// called repeatedly to match several keys.
+    pub fn extended_metadata<T: Into<serde_json::Value>>(self, key: &'a str, value: T) -> Search<'a> {
+        let mut metadata = self.metadata;
+        metadata.insert(Cow::Borrowed(key), value.into());
+        Search { metadata, ..self }
+    }
 }
 
 impl<'a> Default for Search<'a> {
     fn default() -> Self { Self::new() }
 }
 
+fn borrowed<'a>(strs: Vec<&'a str>) -> Vec<Cow<'a, str>> { strs.into_iter().map(Cow::Borrowed).collect() }
+
 pub(crate) mod internal {
+    use super::{Facet, Highlight, Range, Scope, SortDirection, SortField};
+    use crate::utils::serialize;
+
+    use chrono::{DateTime, Utc};
     use serde::Serialize;
+    use std::{borrow::Cow, collections::BTreeMap};
 
     #[derive(Serialize, Debug)]
     pub struct Search<'a> {
-        action: &'a str,
-        params: Params<'a>,
+        action:            &'a str,
+        pub(crate) params: Params<'a>,
     }
 
+    /// The search in the API's request format; saved searches store this format.
     #[derive(Serialize, Debug)]
-    struct Params<'a> {
-        query:  Query<'a>,
-        filter: Filter<'a>,
+    pub(crate) struct Params<'a> {
+        query:     Query,
+        filter:    Filter<'a>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        named:     Option<Vec<Named<'a>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        offset:    Option<usize>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        named:  Option<Vec<Named<'a>>>,
+        rows:      Option<usize>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        sort:      Vec<Sort>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        facets:    Vec<Facet>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        highlight: Option<Highlight>,
     }
 
     #[derive(Serialize, Debug)]
-    struct Query<'a> {
+    struct Query {
         #[serde(skip_serializing_if = "Option::is_none")]
-        text: Option<&'a str>,
+        text: Option<String>,
     }
 
     #[derive(Serialize, Debug)]
     struct Filter<'a> {
         #[serde(skip_serializing_if = "Option::is_none")]
-        filenames: Option<Vec<&'a str>>,
+        filenames:     Option<Vec<Cow<'a, str>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tags:          Option<Vec<Cow<'a, str>>>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "upload-date")]
+        upload_date:   Option<Range<DateTime<Utc>>>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "document-date")]
+        document_date: Option<Range<DateTime<Utc>>>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "version-date")]
+        version_date:  Option<Range<DateTime<Utc>>>,
+        #[serde(
+            skip_serializing_if = "Option::is_none",
+            rename = "mimetypes",
+            serialize_with = "serialize::option_mime_types"
+        )]
+        mime_types:    Option<Vec<mime::Mime>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        owners:        Option<Vec<Cow<'a, str>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        uploaders:     Option<Vec<Cow<'a, str>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        authors:       Option<Vec<Cow<'a, str>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        size:          Option<Range<u64>>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        tags:      Option<Vec<&'a str>>,
+        collections:   Option<Vec<Cow<'a, str>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        collection:    Option<Cow<'a, str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        folder:        Option<Cow<'a, str>>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "include-subfolders")]
+        subfolders:    Option<bool>,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty", rename = "extended-metadata")]
+        metadata:      BTreeMap<Cow<'a, str>, serde_json::Value>,
+    }
+
+    #[derive(Serialize, Debug)]
+    struct Sort {
+        field: SortField,
+        order: SortDirection,
     }
 
     #[derive(Serialize, Debug)]
@@ -107,24 +502,51 @@ pub(crate) mod internal {
 
     impl<'a> Search<'a> {
         pub fn from_search(s: super::Search<'a>) -> Self {
-            let named: Option<Vec<Named>> = match s.named_search {
-                super::NamedSearch::None => None,
-                super::NamedSearch::PublicCollections => {
-                    let include = Include { include: true };
-                    let named = vec![Named {
-                        name:   "public-collections",
-                        params: include,
-                    }];
-                    Some(named)
-                }
+            let named: Option<Vec<Named>> = s.named_search.name().map(|name| {
+                let include = Include { include: true };
+                vec![Named { name, params: include }]
+            });
+
+            let (collection, folder, subfolders) = match s.scope {
+                Some(Scope::Collection(id)) => (Some(id), None, None),
+                Some(Scope::Folder { id, recursive }) => (None, Some(id), Some(recursive)),
+                None => (None, None, None),
             };
 
             let filter = Filter {
                 filenames: s.filenames,
-                tags:      s.tags,
+                tags: s.tags,
+                upload_date: s.upload_date,
+                document_date: s.document_date,
+                version_date: s.version_date,
+                mime_types: s.mime_types,
+                owners: s.owners,
+                uploaders: s.uploaders,
+                authors: s.authors,
+                size: s.size,
+                collections: s.collections,
+                collection,
+                folder,
+                subfolders,
+                metadata: s.metadata,
+            };
+            let text = match (s.fulltext, s.query) {
+                (Some(fulltext), Some(query)) => Some(format!("({}) AND ({})", fulltext, query)),
+                (Some(fulltext), None) => Some(fulltext.into_owned()),
+                (None, Some(query)) => Some(query.to_string()),
+                (None, None) => None,
+            };
+            let query = Query { text };
+            let params = Params {
+                query,
+                filter,
+                named,
+                offset: s.offset,
+                rows: s.rows,
+                sort: s.sort.into_iter().map(|(field, order)| Sort { field, order }).collect(),
+                facets: s.facets,
+                highlight: s.highlight,
             };
-            let query = Query { text: s.fulltext };
-            let params = Params { query, filter, named };
 
             Search {
                 action: "search",
@@ -137,20 +559,61 @@ pub(crate) mod internal {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResult {
     #[serde(default)]
-    pub documents: Vec<Document>,
-    pub hits:      usize,
+    pub documents:  Vec<Document>,
+    pub hits:       usize,
+    /// Document counts per facet value for the facets requested by `Search::facets`.
+    #[serde(default)]
+    pub facets:     HashMap<Facet, HashMap<String, usize>>,
+    /// Snippets per document ID if highlighting has been requested by `Search::highlight`.
+    #[serde(default)]
+    pub highlights: HashMap<ID, Vec<Snippet>>,
+}
+
+/// A fragment of a document's text that matched the fulltext query.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snippet {
+    pub text:    String,
+    #[serde(default)]
+    pub matches: Vec<SnippetMatch>,
+}
+
+/// The position of a match in a snippet's text as character offsets; `end` is exclusive.
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SnippetMatch {
+    pub start: usize,
+    pub end:   usize,
+}
+
+impl Snippet {
+    /// Returns the matched parts of the snippet's text.
+    pub fn matched(&self) -> Vec<&str> {
+        let byte_offset = |chars: usize| {
+            self.text
+                .char_indices()
+                .nth(chars)
+                .map(|(i, _)| i)
+                .unwrap_or_else(|| self.text.len())
+        };
+        self.matches
+            .iter()
+            .map(|m| &self.text[byte_offset(m.start)..byte_offset(m.end.max(m.start))])
+            .collect()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Document {
     pub author:            ID,
-    // collections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collections:       Option<Visibility>,
     pub comments:          usize,
     #[serde(rename = "document-date")]
     pub document_date:     DateTime<FixedOffset>,
     #[serde(rename = "extended-metadata")]
     pub extended_metadata: serde_json::Value,
     pub filename:          String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups:            Option<Visibility>,
     pub hash:              String,
     pub id:                ID,
     #[serde(
@@ -163,14 +626,40 @@ pub struct Document {
     pub pages:             Option<usize>,
     pub representations:   Representations,
     pub score:             Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size:              Option<u64>,
     pub title:             String,
     #[serde(rename = "upload-date")]
     pub upload_date:       DateTime<FixedOffset>,
     pub uploader:          ID,
-    // users
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users:             Option<Visibility>,
     pub version:           usize,
     #[serde(rename = "version-date")]
     pub version_date:      DateTime<FixedOffset>,
+    /// Attributes not modeled by this crate; they are kept so that re-serialized documents are
+    /// complete.
+    #[serde(flatten)]
+    pub extra:             HashMap<String, serde_json::Value>,
+}
+
+/// The IDs of the collections, users, or groups a document belongs to that are visible to the
+/// current user and the number of those that are not.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Visibility {
+    pub visible:           Vec<ID>,
+    #[serde(rename = "not-visible-count")]
+    pub not_visible_count: usize,
+}
+
+impl Document {
+    /// Deserializes the extended metadata into a user-defined type.
+    pub fn extended_metadata_as<T: DeserializeOwned>(&self) -> Result<T> {
+        T::deserialize(&self.extended_metadata).map_err(|e| {
+            e.context(ErrorKind::FailedToProcessExtendedMetadata("deserializing".to_string()))
+                .into()
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -206,15 +695,19 @@ impl fmt::Display for Representations {
 }
 
 pub fn search_documents(authorized_client: &AuthorizedClient, search: Search) -> Result<SearchResult> {
-    let url = format!("https://api.{}/v2/documents", authorized_client.base_url);
-
     let internal_search = internal::Search::from_search(search);
+    post_search(authorized_client, &internal_search)
+}
+
+/// Sends a search request that is already in the API's request format.
+pub(crate) fn post_search<T: Serialize>(authorized_client: &AuthorizedClient, search: &T) -> Result<SearchResult> {
+    let url = format!("https://api.{}/v2/documents", authorized_client.base_url);
 
     let request = authorized_client
         .http_client
         .post(&url)
         .bearer_auth(&authorized_client.token.access_token)
-        .json(&internal_search);
+        .json(search);
     debug!("Request: '{:#?}'", request);
 
     let response: Response = request
@@ -234,17 +727,79 @@ pub fn search_documents(authorized_client: &AuthorizedClient, search: Search) ->
     Ok(result)
 }
 
+/// Iterates over all documents of a search by lazily fetching subsequent result pages.
+pub struct SearchDocumentsIter<'a, C: CenterDevice> {
+    client: &'a C,
+    search: Search<'a>,
+    pages:  Pages,
+}
+
+impl<'a, C: CenterDevice> Iterator for SearchDocumentsIter<'a, C> {
+    type Item = Result<Document>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let SearchDocumentsIter { client, search, pages } = self;
+        pages.next(|offset| client.search_documents(search.clone().offset(offset)))
+    }
+}
+
+pub fn search_documents_iter<'a, C: CenterDevice>(client: &'a C, search: Search<'a>) -> SearchDocumentsIter<'a, C> {
+    let pages = Pages::new(search.offset.unwrap_or(0));
+    SearchDocumentsIter { client, search, pages }
+}
+
+/// The paging state of a `SearchDocumentsIter`; pages are fetched by offset on demand.
+struct Pages {
+    offset:    usize,
+    hits:      Option<usize>,
+    documents: VecDeque<Document>,
+    failed:    bool,
+}
+
+impl Pages {
+    fn new(offset: usize) -> Pages {
+        Pages {
+            offset,
+            hits: None,
+            documents: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    fn next<F: FnOnce(usize) -> Result<SearchResult>>(&mut self, fetch_page: F) -> Option<Result<Document>> {
+        if self.failed {
+            return None;
+        }
+        if self.documents.is_empty() && self.hits.map(|hits| self.offset < hits).unwrap_or(true) {
+            if let Err(e) = self.fetch_next_page(fetch_page) {
+                self.failed = true;
+                return Some(Err(e));
+            }
+        }
+
+        self.documents.pop_front().map(Ok)
+    }
+
+    fn fetch_next_page<F: FnOnce(usize) -> Result<SearchResult>>(&mut self, fetch_page: F) -> Result<()> {
+        let result = fetch_page(self.offset)?;
+        self.hits = Some(result.hits);
+        // Guard against servers returning fewer documents than announced hits.
+        if result.documents.is_empty() {
+            self.offset = result.hits;
+        }
+        self.offset += result.documents.len();
+        self.documents.extend(result.documents);
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use spectral::prelude::*;
 
-    mod document {
-        use super::*;
-
-        #[test]
-        fn deserialize_ok() {
-            let document_json = r#"{
+    static DOCUMENT_JSON: &str = r#"{
     "author": "Industrie- und Handelskammer Bonn/Rhein-Sieg",
     "collections": {
         "not-visible-count": 0,
@@ -283,11 +838,410 @@ mod test {
     "version-date": "2012-12-11T14:31:57.508Z"
 }"#;
 
-            let document: std::result::Result<Document, _> = serde_json::from_str(document_json);
+    mod search {
+        use super::*;
+
+        #[test]
+        fn serialize_paging() {
+            let search = Search::new().fulltext("kartoffel").offset(100).rows(50);
+
+            let json = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+
+            assert_that(&json["params"]["offset"]).is_equal_to(serde_json::json!(100));
+            assert_that(&json["params"]["rows"]).is_equal_to(serde_json::json!(50));
+        }
+
+        #[test]
+        fn serialize_sort() {
+            let search = Search::new()
+                .sort_by(SortField::UploadDate, SortDirection::Descending)
+                .sort_by(SortField::Filename, SortDirection::Ascending);
+
+            let json = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+
+            assert_that(&json["params"]["sort"]).is_equal_to(serde_json::json!([
+                { "field": "upload-date", "order": "desc" },
+                { "field": "filename", "order": "asc" }
+            ]));
+        }
+    }
+
+    mod pages {
+        use super::*;
+
+        fn page(hits: usize, ids: &[&str]) -> Result<SearchResult> {
+            let document: serde_json::Value = serde_json::from_str(DOCUMENT_JSON).expect("Failed to parse fixture");
+            let documents: Vec<serde_json::Value> = ids
+                .iter()
+                .map(|id| {
+                    let mut document = document.clone();
+                    document["id"] = serde_json::json!(id);
+                    document
+                })
+                .collect();
+            let search_result = serde_json::json!({ "hits": hits, "documents": documents });
+            Ok(serde_json::from_value(search_result).expect("Failed to deserialize"))
+        }
+
+        /// Drains the pages and returns the document ids or errors, and the requested offsets.
+        fn collect(offset: usize, mut results: Vec<Result<SearchResult>>) -> (Vec<Result<String>>, Vec<usize>) {
+            results.reverse();
+            let mut pages = Pages::new(offset);
+            let mut offsets = Vec::new();
+            let mut documents = Vec::new();
+            while let Some(document) = pages.next(|offset| {
+                offsets.push(offset);
+                results.pop().expect("Fetched too many pages")
+            }) {
+                documents.push(document.map(|d| d.id));
+            }
+            (documents, offsets)
+        }
+
+        fn strings(ids: &[&str]) -> Vec<String> { ids.iter().map(ToString::to_string).collect() }
+
+        fn ids(documents: Vec<Result<String>>) -> Vec<String> {
+            documents
+                .into_iter()
+                .map(|d| d.expect("Failed to fetch document"))
+                .collect()
+        }
+
+        #[test]
+        fn full_pages() {
+            let (documents, offsets) = collect(0, vec![page(4, &["d1", "d2"]), page(4, &["d3", "d4"])]);
+
+            assert_that(&ids(documents)).is_equal_to(strings(&["d1", "d2", "d3", "d4"]));
+            assert_that(&offsets).is_equal_to(vec![0, 2]);
+        }
+
+        #[test]
+        fn short_last_page() {
+            let (documents, offsets) = collect(10, vec![page(13, &["d1", "d2"]), page(13, &["d3"])]);
+
+            assert_that(&ids(documents)).is_equal_to(strings(&["d1", "d2", "d3"]));
+            assert_that(&offsets).is_equal_to(vec![10, 12]);
+        }
+
+        #[test]
+        fn empty_page_before_hits() {
+            let (documents, offsets) = collect(0, vec![page(5, &["d1", "d2"]), page(5, &[])]);
+
+            assert_that(&ids(documents)).is_equal_to(strings(&["d1", "d2"]));
+            assert_that(&offsets).is_equal_to(vec![0, 2]);
+        }
+
+        #[test]
+        fn stop_after_error() {
+            let error = Err(Error::from(ErrorKind::HttpRequestFailed));
+            let (documents, offsets) = collect(0, vec![page(4, &["d1", "d2"]), error, page(4, &["d3", "d4"])]);
+
+            let documents: Vec<std::result::Result<String, ErrorKind>> =
+                documents.into_iter().map(|d| d.map_err(|e| e.kind().clone())).collect();
+            assert_that(&documents).is_equal_to(vec![
+                Ok("d1".to_string()),
+                Ok("d2".to_string()),
+                Err(ErrorKind::HttpRequestFailed),
+            ]);
+            assert_that(&offsets).is_equal_to(vec![0, 2]);
+        }
+    }
+
+    mod structured_query {
+        use super::*;
+        use crate::client::search::query::{Field, Query};
+
+        #[test]
+        fn serialize_query() {
+            let query = Query::field(Field::Tag, "A")
+                .and(Query::field(Field::Tag, "B").or(Query::field(Field::Tag, "C")))
+                .and(!Query::field(Field::MimeType, "video/*"));
+            let search = Search::new().query(query).rows(10);
+
+            let json = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+
+            let expected: serde_json::Value = serde_json::from_str(
+                r#"{
+    "action": "search",
+    "params": {
+        "query": {
+            "text": "tag:A AND (tag:B OR tag:C) AND NOT mimetype:video\\/*"
+        },
+        "filter": {},
+        "rows": 10
+    }
+}"#,
+            )
+            .expect("Failed to parse fixture");
+            assert_that(&json).is_equal_to(expected);
+        }
+
+        #[test]
+        fn serialize_query_with_fulltext() {
+            let query = Query::range(Field::Size, Range::since(1024u64));
+            let search = Search::new().fulltext("kartoffel").query(query);
+
+            let json = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+
+            let expected: serde_json::Value = serde_json::from_str(
+                r#"{
+    "action": "search",
+    "params": {
+        "query": {
+            "text": "(kartoffel) AND (size:[1024 TO *])"
+        },
+        "filter": {}
+    }
+}"#,
+            )
+            .expect("Failed to parse fixture");
+            assert_that(&json).is_equal_to(expected);
+        }
+    }
+
+    mod roundtrip {
+        use super::*;
+        use crate::client::search::query::{Field, Query};
+
+        #[test]
+        fn search_roundtrip() {
+            let search = Search::new()
+                .fulltext("Grüße \"aus\" Köln")
+                .query(
+                    Query::field(Field::Tag, "A")
+                        .and(!Query::field(Field::Other(Cow::Borrowed("language")), "en"))
+                        .and(Query::range(
+                            Field::Size,
+                            Range::between(5u64, 10).expect("Failed to build range"),
+                        )),
+                )
+                .folder("9da0ffc7-09a5-42ee-a166-05ff13a74d91", true)
+                .named_searches(NamedSearch::MyDocuments)
+                .sort_by(SortField::Title, SortDirection::Ascending)
+                .mime_types(vec!["application/pdf".parse().expect("Failed to parse mime type")])
+                .upload_date(Range::since(
+                    "2019-01-01T00:00:00Z".parse().expect("Failed to parse date"),
+                ))
+                .tags(vec!["B"]);
+
+            let json = serde_json::to_string(&search).expect("Failed to serialize");
+            let restored: Search<'static> = serde_json::from_str(&json).expect("Failed to deserialize");
+
+            let expected = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+            let actual = serde_json::to_value(internal::Search::from_search(restored)).expect("Failed to serialize");
+            assert_that(&actual).is_equal_to(expected);
+        }
+    }
+
+    mod scope {
+        use super::*;
+
+        #[test]
+        fn serialize_folder_scope() {
+            let search = Search::new()
+                .folder("9da0ffc7-09a5-42ee-a166-05ff13a74d91", true)
+                .named_searches(NamedSearch::SharedWithMe);
+
+            let json = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+
+            let expected: serde_json::Value = serde_json::from_str(
+                r#"{
+    "action": "search",
+    "params": {
+        "query": {},
+        "filter": {
+            "folder": "9da0ffc7-09a5-42ee-a166-05ff13a74d91",
+            "include-subfolders": true
+        },
+        "named": [
+            { "name": "shared-with-me", "params": { "include": true } }
+        ]
+    }
+}"#,
+            )
+            .expect("Failed to parse fixture");
+            assert_that(&json).is_equal_to(expected);
+        }
+    }
+
+    mod filter {
+        use super::*;
+
+        #[test]
+        fn serialize_filters() {
+            let search = Search::new()
+                .upload_date(
+                    Range::between(
+                        "2019-01-01T00:00:00Z".parse().expect("Failed to parse date"),
+                        "2019-12-31T23:59:59Z".parse().expect("Failed to parse date"),
+                    )
+                    .expect("Failed to build range"),
+                )
+                .mime_types(vec!["application/pdf".parse().expect("Failed to parse mime type")])
+                .owners(vec!["ded4d798-d659-4c1c-9f2d-09e02d23e604"])
+                .size(Range::until(1024));
+
+            let json = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+
+            assert_that(&json["params"]["filter"]).is_equal_to(serde_json::json!({
+                "upload-date": { "from": "2019-01-01T00:00:00Z", "to": "2019-12-31T23:59:59Z" },
+                "mimetypes": ["application/pdf"],
+                "owners": ["ded4d798-d659-4c1c-9f2d-09e02d23e604"],
+                "size": { "to": 1024 }
+            }));
+        }
+
+        #[test]
+        fn serialize_extended_metadata_filter() {
+            let search = Search::new()
+                .extended_metadata("customer-id", 4711)
+                .extended_metadata("department", "sales");
+
+            let json = serde_json::to_value(internal::Search::from_search(search)).expect("Failed to serialize");
+
+            assert_that(&json["params"]["filter"]).is_equal_to(serde_json::json!({
+                "extended-metadata": { "customer-id": 4711, "department": "sales" }
+            }));
+        }
+
+        #[test]
+        fn reject_invalid_range() {
+            let range = Range::between(1024u64, 1);
+            let deserialized = serde_json::from_str::<Range<u64>>(r#"{ "from": 1024, "to": 1 }"#);
+
+            assert_that(&range).is_err();
+            assert_that(&deserialized).is_err();
+        }
+    }
+
+    mod search_result {
+        use super::*;
+
+        #[test]
+        fn deserialize_facets() {
+            let search_result_json = r#"{
+    "documents": [],
+    "hits": 3,
+    "facets": {
+        "tags": {
+            "A": 3,
+            "B": 1
+        },
+        "mimetypes": {
+            "application/pdf": 2,
+            "video/mp4": 1
+        },
+        "languages": {
+            "de": 3
+        },
+        "years": {
+            "2019": 2
+        }
+    }
+}"#;
+
+            let search_result: SearchResult = serde_json::from_str(search_result_json).expect("Failed to deserialize");
+
+            assert_that(&search_result.facets[&Facet::Tags]["A"]).is_equal_to(3);
+            assert_that(&search_result.facets[&Facet::MimeTypes]["video/mp4"]).is_equal_to(1);
+            assert_that(&search_result.facets[&Facet::Other("languages".to_string())]["de"]).is_equal_to(3);
+            assert_that(&search_result.facets[&Facet::Other("years".to_string())]["2019"]).is_equal_to(2);
+        }
+
+        #[test]
+        fn deserialize_highlights() {
+            let search_result_json = r#"{
+    "hits": 1,
+    "highlights": {
+        "0176fc13-6dfe-40db-aca7-6b7c729e3fa3": [
+            {
+                "text": "Anbau von Kartoffeln und Rüben",
+                "matches": [ { "start": 10, "end": 20 } ]
+            }
+        ]
+    }
+}"#;
+
+            let search_result: SearchResult = serde_json::from_str(search_result_json).expect("Failed to deserialize");
+            let snippets = &search_result.highlights["0176fc13-6dfe-40db-aca7-6b7c729e3fa3"];
+
+            assert_that(&snippets[0].matched()).is_equal_to(vec!["Kartoffeln"]);
+        }
+    }
+
+    mod document {
+        use super::*;
+
+        #[test]
+        fn deserialize_ok() {
+            let document: std::result::Result<Document, _> = serde_json::from_str(DOCUMENT_JSON);
 
             assert_that(&document).is_ok();
 
-            println!("Document: {:#?}", document.unwrap());
+            let document = document.unwrap();
+            println!("Document: {:#?}", document);
+            assert_that(&document.size).is_equal_to(Some(819_693));
+            assert_that(&document.collections.as_ref().map(|c| c.visible.len())).is_equal_to(Some(1));
+            assert_that(&document.users.as_ref().map(|u| u.not_visible_count)).is_equal_to(Some(0));
+            assert_that(&document.extra.is_empty()).is_true();
+        }
+
+        #[test]
+        fn extended_metadata_as() {
+            #[derive(Deserialize, PartialEq, Debug)]
+            struct Invoice {
+                #[serde(rename = "customer-id")]
+                customer_id: u32,
+                paid:        bool,
+            }
+            let mut document_json: serde_json::Value =
+                serde_json::from_str(DOCUMENT_JSON).expect("Failed to parse fixture");
+            document_json["extended-metadata"] = serde_json::json!({ "customer-id": 4711, "paid": true });
+            let document: Document = serde_json::from_value(document_json).expect("Failed to deserialize");
+
+            let invoice: Result<Invoice> = document.extended_metadata_as();
+
+            assert_that(&invoice.map_err(|e| e.to_string())).is_equal_to(Ok(Invoice {
+                customer_id: 4711,
+                paid:        true,
+            }));
+        }
+
+        #[test]
+        fn roundtrip_unknown_fields() {
+            let document_json = r#"{
+    "author": "Industrie- und Handelskammer Bonn/Rhein-Sieg",
+    "comments": 0,
+    "document-date": "2012-12-11T14:31:57.508Z",
+    "extended-metadata": {},
+    "filename": "Branchenkatalog.pdf",
+    "hash": "fbf2b3b1688f94c76f10adfc82f80c1d",
+    "id": "0176fc13-6dfe-40db-aca7-6b7c729e3fa3",
+    "language": "de",
+    "mimetype": "application/pdf",
+    "owner": "ded4d798-d659-4c1c-9f2d-09e02d23e604",
+    "representations": {
+        "fulltext": "yes",
+        "jpg": "yes",
+        "mp4": "no",
+        "pdf": "yes",
+        "png": "no"
+    },
+    "title": "NACE- Klassifikation der Wirtschaftszweige 2008",
+    "upload-date": "2012-12-11T14:31:57.508Z",
+    "uploader": "ded4d798-d659-4c1c-9f2d-09e02d23e604",
+    "version": 1,
+    "version-date": "2012-12-11T14:31:57.508Z"
+}"#;
+
+            let document: Document = serde_json::from_str(document_json).expect("Failed to deserialize");
+            let json = serde_json::to_value(&document).expect("Failed to serialize");
+
+            assert_that(&document.extra["language"]).is_equal_to(serde_json::json!("de"));
+            assert_that(&json["language"]).is_equal_to(serde_json::json!("de"));
+            for key in &["collections", "groups", "size", "users"] {
+                assert_that(&json.get(key)).is_none();
+            }
         }
     }
 }
@@ -0,0 +1,262 @@
+use crate::client::search::Range;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt, ops::Not};
+
+/// A document field that can be matched in a `Query`.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Field<'a> {
+    Author,
+    Collection,
+    DocumentDate,
+    Filename,
+    Fulltext,
+    MimeType,
+    Owner,
+    Size,
+    Tag,
+    Title,
+    UploadDate,
+    Uploader,
+    VersionDate,
+    Other(Cow<'a, str>),
+}
+
+impl<'a> Field<'a> {
+    fn name(&self) -> &str {
+        match *self {
+            Field::Author => "author",
+            Field::Collection => "collection",
+            Field::DocumentDate => "document-date",
+            Field::Filename => "filename",
+            Field::Fulltext => "fulltext",
+            Field::MimeType => "mimetype",
+            Field::Owner => "owner",
+            Field::Size => "size",
+            Field::Tag => "tag",
+            Field::Title => "title",
+            Field::UploadDate => "upload-date",
+            Field::Uploader => "uploader",
+            Field::VersionDate => "version-date",
+            Field::Other(ref name) => name,
+        }
+    }
+}
+
+/// A value that can be used as bound of a range query.
+pub trait RangeValue {
+    fn render(&self) -> String;
+}
+
+impl RangeValue for DateTime<Utc> {
+    fn render(&self) -> String { self.to_rfc3339_opts(SecondsFormat::Secs, true) }
+}
+
+impl RangeValue for u64 {
+    fn render(&self) -> String { self.to_string() }
+}
+
+/// The rendered bounds of a range query. They are not validated again, because rendered bounds do
+/// not compare like the values they were rendered from, e.g., `"5" > "10"`.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct QueryRange {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to:   Option<String>,
+}
+
+impl QueryRange {
+    pub fn from(&self) -> Option<&String> { self.from.as_ref() }
+
+    pub fn to(&self) -> Option<&String> { self.to.as_ref() }
+}
+
+/// A composable search query, e.g., `tag:A AND (tag:B OR tag:C) AND NOT mimetype:video/*`. Its
+/// `Display` implementation renders the query in the syntax of CenterDevice's fulltext search.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Query<'a> {
+    /// A single term; the wildcards `*` and `?` are supported.
+    Term(Cow<'a, str>),
+    /// An exact phrase.
+    Phrase(Cow<'a, str>),
+    /// A term that must match in a specific field; the wildcards `*` and `?` are supported.
+    Match(Field<'a>, Cow<'a, str>),
+    /// A range of values in a specific field.
+    Range(Field<'a>, QueryRange),
+    /// All queries must match; an empty conjunction matches all documents.
+    And(Vec<Query<'a>>),
+    /// At least one query must match; an empty disjunction matches no documents.
+    Or(Vec<Query<'a>>),
+    Not(Box<Query<'a>>),
+}
+
+impl<'a> Query<'a> {
+    pub fn term(term: &'a str) -> Query<'a> { Query::Term(Cow::Borrowed(term)) }
+
+    pub fn phrase(phrase: &'a str) -> Query<'a> { Query::Phrase(Cow::Borrowed(phrase)) }
+
+    pub fn field(field: Field<'a>, value: &'a str) -> Query<'a> { Query::Match(field, Cow::Borrowed(value)) }
+
+    pub fn range<T: RangeValue>(field: Field<'a>, range: Range<T>) -> Query<'a> {
+        let range = QueryRange {
+            from: range.from.as_ref().map(RangeValue::render),
+            to:   range.to.as_ref().map(RangeValue::render),
+        };
+        Query::Range(field, range)
+    }
+
+    pub fn and(self, other: Query<'a>) -> Query<'a> {
+        match self {
+            Query::And(mut queries) => {
+                queries.push(other);
+                Query::And(queries)
+            }
+            query => Query::And(vec![query, other]),
+        }
+    }
+
+    pub fn or(self, other: Query<'a>) -> Query<'a> {
+        match self {
+            Query::Or(mut queries) => {
+                queries.push(other);
+                Query::Or(queries)
+            }
+            query => Query::Or(vec![query, other]),
+        }
+    }
+
+    fn is_compound(&self) -> bool {
+        match *self {
+            Query::And(ref queries) | Query::Or(ref queries) => queries.len() > 1,
+            _ => false,
+        }
+    }
+
+    fn fmt_nested(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_compound() {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+
+    fn fmt_joined(queries: &[Query], operator: &str, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, query) in queries.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", operator)?;
+            }
+            query.fmt_nested(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Not for Query<'a> {
+    type Output = Query<'a>;
+
+    fn not(self) -> Query<'a> { Query::Not(Box::new(self)) }
+}
+
+impl<'a> fmt::Display for Query<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Query::Term(ref term) => write!(f, "{}", escape(term, TERM_SPECIAL_CHARS)),
+            Query::Phrase(ref phrase) => write!(f, "\"{}\"", phrase.replace('\\', "\\\\").replace('"', "\\\"")),
+            Query::Match(ref field, ref value) => write!(f, "{}:{}", field.name(), escape(value, TERM_SPECIAL_CHARS)),
+            Query::Range(ref field, ref range) => {
+                let bound = |b: &Option<String>| {
+                    b.as_ref()
+                        .map(|s| escape(s, RANGE_SPECIAL_CHARS))
+                        .unwrap_or_else(|| "*".to_string())
+                };
+                let (from, to) = (bound(&range.from), bound(&range.to));
+                write!(f, "{}:[{} TO {}]", field.name(), from, to)
+            }
+            Query::And(ref queries) if queries.is_empty() => write!(f, "*:*"),
+            Query::And(ref queries) => Query::fmt_joined(queries, "AND", f),
+            Query::Or(ref queries) if queries.is_empty() => write!(f, "NOT *:*"),
+            Query::Or(ref queries) => Query::fmt_joined(queries, "OR", f),
+            Query::Not(ref query) => {
+                write!(f, "NOT ")?;
+                query.fmt_nested(f)
+            }
+        }
+    }
+}
+
+/// Characters with special meaning in terms; the wildcards `*` and `?` are deliberately missing.
+const TERM_SPECIAL_CHARS: &[char] = &[
+    '\\', '+', '-', '&', '|', '!', '(', ')', '{', '}', '[', ']', '^', '"', '~', ':', '/', ' ',
+];
+
+/// Characters with special meaning in range bounds.
+const RANGE_SPECIAL_CHARS: &[char] = &['\\', '[', ']', '{', '}', '"', ' ', '*'];
+
+fn escape(s: &str, special_chars: &[char]) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if special_chars.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn render_terms() {
+        assert_that(&Query::term("kartoffel*").to_string()).is_equal_to("kartoffel*".to_string());
+        assert_that(&Query::phrase("Grüße aus \"Köln\"").to_string())
+            .is_equal_to(r#""Grüße aus \"Köln\"""#.to_string());
+        assert_that(&Query::field(Field::Filename, "Branchenkatalog (1).pdf").to_string())
+            .is_equal_to(r#"filename:Branchenkatalog\ \(1\).pdf"#.to_string());
+    }
+
+    #[test]
+    fn render_ranges() {
+        let dates = Query::range(
+            Field::UploadDate,
+            Range::since(
+                "2019-01-01T00:00:00Z"
+                    .parse::<DateTime<Utc>>()
+                    .expect("Failed to parse date"),
+            ),
+        );
+        let sizes = Query::range(Field::Size, Range::between(1u64, 1024).expect("Failed to build range"));
+
+        assert_that(&dates.to_string()).is_equal_to("upload-date:[2019-01-01T00:00:00Z TO *]".to_string());
+        assert_that(&sizes.to_string()).is_equal_to("size:[1 TO 1024]".to_string());
+    }
+
+    #[test]
+    fn render_boolean_logic() {
+        let query = Query::field(Field::Tag, "A")
+            .and(Query::field(Field::Tag, "B").or(Query::field(Field::Tag, "C")))
+            .and(!Query::field(Field::MimeType, "video/*"));
+
+        assert_that(&query.to_string())
+            .is_equal_to(r#"tag:A AND (tag:B OR tag:C) AND NOT mimetype:video\/*"#.to_string());
+    }
+
+    #[test]
+    fn render_nested_negation() {
+        let query = !Query::term("a").or(Query::term("b"));
+
+        assert_that(&query.to_string()).is_equal_to("NOT (a OR b)".to_string());
+    }
+
+    #[test]
+    fn render_empty() {
+        assert_that(&Query::And(vec![]).to_string()).is_equal_to("*:*".to_string());
+        assert_that(&Query::Or(vec![]).to_string()).is_equal_to("NOT *:*".to_string());
+    }
+}
@@ -0,0 +1,488 @@
+use crate::{
+    client::{delete::FailedDocuments, AuthorizedClient, GeneralErrHandler, ID},
+    errors::{Error, ErrorKind, Result},
+};
+
+use failure::Fail;
+use log::debug;
+use reqwest::{blocking::Response, StatusCode};
+use serde::{self, Deserialize, Serialize};
+use std::{cmp::Ordering, string::ToString};
+
+/// Access level of a member; levels are ordered, i.e., each level includes the rights of the lower
+/// ones. Levels unknown to this crate are preserved in `Unknown` and are not comparable.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Permission {
+    /// May view and download documents.
+    Viewer,
+    /// May additionally upload, edit, and remove documents.
+    Editor,
+    /// May additionally manage members and folders.
+    Manager,
+    /// Owns the collection; ownership cannot be granted via membership.
+    Owner,
+    Unknown(String),
+}
+
+impl Permission {
+    fn level(&self) -> Option<u8> {
+        match *self {
+            Permission::Viewer => Some(0),
+            Permission::Editor => Some(1),
+            Permission::Manager => Some(2),
+            Permission::Owner => Some(3),
+            Permission::Unknown(_) => None,
+        }
+    }
+}
+
+impl PartialOrd for Permission {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.level(), other.level()) {
+            (Some(level), Some(other_level)) => Some(level.cmp(&other_level)),
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Permission {
+    fn from(permission: String) -> Self {
+        match permission.as_str() {
+            "viewer" => Permission::Viewer,
+            "editor" => Permission::Editor,
+            "manager" => Permission::Manager,
+            "owner" => Permission::Owner,
+            _ => Permission::Unknown(permission),
+        }
+    }
+}
+
+impl From<Permission> for String {
+    fn from(permission: Permission) -> Self {
+        match permission {
+            Permission::Viewer => "viewer".to_string(),
+            Permission::Editor => "editor".to_string(),
+            Permission::Manager => "manager".to_string(),
+            Permission::Owner => "owner".to_string(),
+            Permission::Unknown(permission) => permission,
+        }
+    }
+}
+
+/// Kind of a member; kinds unknown to this crate are preserved in `Unknown`.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum MemberKind {
+    User,
+    Group,
+    Unknown(String),
+}
+
+impl From<String> for MemberKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "user" => MemberKind::User,
+            "group" => MemberKind::Group,
+            _ => MemberKind::Unknown(kind),
+        }
+    }
+}
+
+impl From<MemberKind> for String {
+    fn from(kind: MemberKind) -> Self {
+        match kind {
+            MemberKind::User => "user".to_string(),
+            MemberKind::Group => "group".to_string(),
+            MemberKind::Unknown(kind) => kind,
+        }
+    }
+}
+
+/// A user or group to share with.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Principal<'a> {
+    User(&'a str),
+    Group(&'a str),
+}
+
+impl<'a> Principal<'a> {
+    fn kind(&self) -> MemberKind {
+        match *self {
+            Principal::User(_) => MemberKind::User,
+            Principal::Group(_) => MemberKind::Group,
+        }
+    }
+
+    fn id(&self) -> &'a str {
+        match *self {
+            Principal::User(id) | Principal::Group(id) => id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MembersResult {
+    pub members: Vec<Member>,
+}
+
+impl Default for MembersResult {
+    fn default() -> MembersResult {
+        MembersResult {
+            members: Vec::with_capacity(0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Member {
+    pub id:         ID,
+    #[serde(rename = "type")]
+    pub kind:       MemberKind,
+    pub permission: Permission,
+}
+
+impl Member {
+    /// The user or group of this member; `None` if its kind is unknown.
+    pub fn principal(&self) -> Option<Principal<'_>> {
+        match self.kind {
+            MemberKind::User => Some(Principal::User(&self.id)),
+            MemberKind::Group => Some(Principal::Group(&self.id)),
+            MemberKind::Unknown(_) => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct MemberGrant<'a> {
+    id:         &'a str,
+    #[serde(rename = "type")]
+    kind:       MemberKind,
+    permission: Permission,
+}
+
+impl<'a> MemberGrant<'a> {
+    /// Fails with `ErrorKind::InvalidPermission` for `Permission::Owner`, which cannot be granted.
+    fn new(principal: Principal<'a>, permission: Permission) -> Result<Self> {
+        if permission == Permission::Owner {
+            return Err(Error::from(ErrorKind::InvalidPermission(
+                "ownership cannot be granted via membership".to_string(),
+            )));
+        }
+
+        Ok(MemberGrant {
+            id: principal.id(),
+            kind: principal.kind(),
+            permission,
+        })
+    }
+}
+
+/// The users and groups a document is shared with.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Shares {
+    #[serde(default)]
+    pub users:  Vec<ID>,
+    #[serde(default)]
+    pub groups: Vec<ID>,
+}
+
+#[derive(Serialize, Debug)]
+struct ShareAction<'a> {
+    action: &'a str,
+    params: ShareParams<'a>,
+}
+
+#[derive(Serialize, Debug)]
+struct ShareParams<'a> {
+    documents: &'a [&'a str],
+    users:     Vec<&'a str>,
+    groups:    Vec<&'a str>,
+}
+
+impl<'a> ShareAction<'a> {
+    fn new(action: &'a str, documents: &'a [&'a str], principals: &[Principal<'a>]) -> Self {
+        let ids = |kind| principals.iter().filter(|p| p.kind() == kind).map(|p| p.id()).collect();
+        let params = ShareParams {
+            documents,
+            users: ids(MemberKind::User),
+            groups: ids(MemberKind::Group),
+        };
+        ShareAction { action, params }
+    }
+}
+
+pub fn collection_members(authorized_client: &AuthorizedClient, collection_id: &str) -> Result<MembersResult> {
+    let url = format!(
+        "https://api.{}/v2/collection/{}/members",
+        authorized_client.base_url, collection_id
+    );
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK, StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    let result = match response.status() {
+        status @ StatusCode::OK => {
+            response.json().map_err(|e| {
+                e.context(ErrorKind::FailedToProcessHttpResponse(
+                    status,
+                    "reading body".to_string(),
+                ))
+            })?
+        }
+        StatusCode::NO_CONTENT => MembersResult::default(),
+        code => {
+            return Err(Error::from(ErrorKind::ApiCallFailed(
+                code,
+                "unexpected response code".to_string(),
+            )))
+        }
+    };
+
+    Ok(result)
+}
+
+/// Adds a user or group as member; `Permission::Owner` cannot be granted.
+pub fn add_collection_member(
+    authorized_client: &AuthorizedClient,
+    collection_id: &str,
+    principal: Principal,
+    permission: Permission,
+) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/collection/{}/members",
+        authorized_client.base_url, collection_id
+    );
+
+    let grant = MemberGrant::new(principal, permission)?;
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&grant);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+/// Changes the access level of an existing member; `Permission::Owner` cannot be granted.
+pub fn set_collection_member_permission(
+    authorized_client: &AuthorizedClient,
+    collection_id: &str,
+    principal: Principal,
+    permission: Permission,
+) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/collection/{}/members",
+        authorized_client.base_url, collection_id
+    );
+
+    let grant = MemberGrant::new(principal, permission)?;
+
+    let request = authorized_client
+        .http_client
+        .put(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&grant);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+pub fn remove_collection_member(
+    authorized_client: &AuthorizedClient,
+    collection_id: &str,
+    principal: Principal,
+) -> Result<()> {
+    let url = format!(
+        "https://api.{}/v2/collection/{}/members",
+        authorized_client.base_url, collection_id
+    );
+
+    let kind = String::from(principal.kind());
+    let params = [("type", kind.as_str()), ("id", principal.id())];
+
+    let request = authorized_client
+        .http_client
+        .delete(&url)
+        .query(&params)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+pub fn share_documents(
+    authorized_client: &AuthorizedClient,
+    document_ids: &[&str],
+    principals: &[Principal],
+) -> Result<()> {
+    documents_share_action(authorized_client, ShareAction::new("share", document_ids, principals))
+}
+
+pub fn unshare_documents(
+    authorized_client: &AuthorizedClient,
+    document_ids: &[&str],
+    principals: &[Principal],
+) -> Result<()> {
+    documents_share_action(authorized_client, ShareAction::new("unshare", document_ids, principals))
+}
+
+/// Fails with `ErrorKind::FailedDocuments` if some documents could not be shared or unshared.
+fn documents_share_action(authorized_client: &AuthorizedClient, share_action: ShareAction) -> Result<()> {
+    let url = format!("https://api.{}/v2/documents", authorized_client.base_url);
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&share_action);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK, StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    if response.status() == StatusCode::OK {
+        let status = response.status();
+        let failed_documents: FailedDocuments = response.json().map_err(|e| {
+            e.context(ErrorKind::FailedToProcessHttpResponse(
+                status,
+                "reading body".to_string(),
+            ))
+        })?;
+        if !failed_documents.failed_documents.is_empty() {
+            return Err(Error::from(ErrorKind::FailedDocuments(
+                failed_documents.failed_documents,
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists the users and groups a document is shared with.
+pub fn document_shares(authorized_client: &AuthorizedClient, document_id: &str) -> Result<Shares> {
+    let url = format!(
+        "https://api.{}/v2/document/{}/shares",
+        authorized_client.base_url, document_id
+    );
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    static MEMBERS_JSON: &str = r#"{
+    "members": [
+        { "id": "u1", "type": "user", "permission": "owner" },
+        { "id": "u2", "type": "user", "permission": "viewer" },
+        { "id": "g1", "type": "group", "permission": "editor" },
+        { "id": "x1", "type": "robot", "permission": "auditor" }
+    ]
+}"#;
+
+    #[test]
+    fn deserialize_members() {
+        let result: MembersResult = serde_json::from_str(MEMBERS_JSON).expect("Failed to deserialize");
+
+        let editors: Vec<Principal> = result
+            .members
+            .iter()
+            .filter(|m| m.permission >= Permission::Editor)
+            .filter_map(Member::principal)
+            .collect();
+
+        assert_that(&editors).is_equal_to(vec![Principal::User("u1"), Principal::Group("g1")]);
+        assert_that(&result.members[3].kind).is_equal_to(MemberKind::Unknown("robot".to_string()));
+        assert_that(&result.members[3].permission).is_equal_to(Permission::Unknown("auditor".to_string()));
+    }
+
+    #[test]
+    fn serialize_member_grant() {
+        let grant = MemberGrant::new(Principal::Group("g1"), Permission::Manager).expect("Failed to grant");
+
+        let json = serde_json::to_value(&grant).expect("Failed to serialize");
+
+        assert_that(&json).is_equal_to(serde_json::json!({"id": "g1", "type": "group", "permission": "manager"}));
+    }
+
+    #[test]
+    fn reject_owner_grant() {
+        let grant = MemberGrant::new(Principal::User("u1"), Permission::Owner);
+
+        assert_that(&grant.map(|_| ()).map_err(|e| e.kind().clone())).is_equal_to(Err(ErrorKind::InvalidPermission(
+            "ownership cannot be granted via membership".to_string(),
+        )));
+    }
+
+    #[test]
+    fn serialize_share_action() {
+        let share_action = ShareAction::new(
+            "share",
+            &["d1", "d2"],
+            &[Principal::User("u1"), Principal::Group("g1"), Principal::User("u2")],
+        );
+
+        let json = serde_json::to_value(&share_action).expect("Failed to serialize");
+
+        assert_that(&json).is_equal_to(serde_json::json!({
+            "action": "share",
+            "params": { "documents": ["d1", "d2"], "users": ["u1", "u2"], "groups": ["g1"] }
+        }));
+    }
+}
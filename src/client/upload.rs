@@ -3,25 +3,30 @@ use crate::{
     errors::{ErrorKind, Result},
 };
 
+use chrono::{DateTime, Utc};
 use failure::Fail;
 use hex;
 use log::debug;
 use mime::*;
 use mime_multipart::{write_multipart, FilePart, Node, Part};
 use reqwest::{blocking::Response, header, StatusCode};
-use serde::{self, Deserialize};
+use serde::Serialize;
 use std::{borrow::Cow, path::Path};
 
 #[derive(Debug)]
 pub struct Upload<'a> {
-    path:        &'a Path,
-    mime_type:   Mime,
-    filename:    Cow<'a, str>,
-    size:        u64,
-    title:       Option<&'a str>,
-    author:      Option<&'a str>,
-    tags:        &'a [&'a str],
-    collections: &'a [&'a str],
+    path:          &'a Path,
+    mime_type:     Mime,
+    filename:      Cow<'a, str>,
+    size:          u64,
+    title:         Option<&'a str>,
+    author:        Option<&'a str>,
+    tags:          &'a [&'a str],
+    collections:   &'a [&'a str],
+    metadata:      Option<serde_json::Value>,
+    document_date: Option<DateTime<Utc>>,
+    language:      Option<&'a str>,
+    description:   Option<&'a str>,
 }
 
 impl<'a> Upload<'a> {
@@ -45,6 +50,10 @@ impl<'a> Upload<'a> {
             author: None,
             tags: &[],
             collections: &[],
+            metadata: None,
+            document_date: None,
+            language: None,
+            description: None,
         })
     }
 
@@ -66,12 +75,50 @@ impl<'a> Upload<'a> {
         }
     }
 
+    /// Sets the original date of the document, e.g., the date a letter was written.
+    pub fn document_date(self, document_date: DateTime<Utc>) -> Upload<'a> {
+        Upload {
+            document_date: Some(document_date),
+            ..self
+        }
+    }
+
+    /// Sets the language of the document as ISO 639-1 code, e.g., `de`.
+    pub fn language(self, language: &'a str) -> Upload<'a> {
+        Upload {
+            language: Some(language),
+            ..self
+        }
+    }
+
+    pub fn description(self, description: &'a str) -> Upload<'a> {
+        Upload {
+            description: Some(description),
+            ..self
+        }
+    }
+
     pub fn tags(self, tags: &'a [&str]) -> Upload<'a> { Upload { tags, ..self } }
 
     pub fn collections(self, collections: &'a [&str]) -> Upload<'a> { Upload { collections, ..self } }
+
+    /// Sets the extended metadata of the document; `metadata` must serialize to a JSON object.
+    pub fn extended_metadata<T: Serialize>(self, metadata: &T) -> Result<Upload<'a>> {
+        let metadata = serde_json::to_value(metadata)
+            .map_err(|e| e.context(ErrorKind::FailedToProcessExtendedMetadata("serializing".to_string())))?;
+        if !metadata.is_object() {
+            return Err(ErrorKind::FailedToProcessExtendedMetadata("not a JSON object".to_string()).into());
+        }
+
+        Ok(Upload {
+            metadata: Some(metadata),
+            ..self
+        })
+    }
 }
 
 pub(crate) mod internal {
+    use chrono::{DateTime, Utc};
     use serde::Serialize;
 
     #[derive(Serialize, Debug)]
@@ -88,12 +135,20 @@ pub(crate) mod internal {
 
     #[derive(Serialize, Debug)]
     struct Document<'a> {
-        filename: &'a str,
-        size:     u64,
+        filename:      &'a str,
+        size:          u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title:         Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        author:        Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none", rename(serialize = "document-date"))]
+        document_date: Option<DateTime<Utc>>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        title:    Option<&'a str>,
+        language:      Option<&'a str>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        author:   Option<&'a str>,
+        description:   Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none", rename(serialize = "extended-metadata"))]
+        metadata:      Option<&'a serde_json::Value>,
     }
 
     #[derive(Serialize, Debug)]
@@ -107,10 +162,14 @@ pub(crate) mod internal {
     impl<'a> DocumentMetadata<'a> {
         pub fn from_upload(u: &'a super::Upload<'a>) -> Self {
             let document = Document {
-                filename: u.filename.as_ref(),
-                size:     u.size,
-                title:    u.title,
-                author:   u.author,
+                filename:      u.filename.as_ref(),
+                size:          u.size,
+                title:         u.title,
+                author:        u.author,
+                document_date: u.document_date,
+                language:      u.language,
+                description:   u.description,
+                metadata:      u.metadata.as_ref(),
             };
             let actions = Actions {
                 tags:        Some(u.tags),
@@ -126,11 +185,6 @@ pub(crate) mod internal {
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct Id {
-    id: client::ID,
-}
-
 pub fn upload_file(authorized_client: &AuthorizedClient, upload: Upload) -> Result<client::ID> {
     let url = format!("https://api.{}/v2/documents", authorized_client.base_url);
 
@@ -166,7 +220,7 @@ pub fn upload_file(authorized_client: &AuthorizedClient, upload: Upload) -> Resu
     debug!("Response: '{:#?}'", response);
 
     let status = response.status();
-    let result: Id = response.json().map_err(|e| {
+    let result: client::Id = response.json().map_err(|e| {
         e.context(ErrorKind::FailedToProcessHttpResponse(
             status,
             "reading body".to_string(),
@@ -222,3 +276,30 @@ fn generate_boundary(seed: &[u8]) -> String {
     let sha_str = hex::encode(sha.as_ref());
     format!("Boundary_{}", sha_str)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn serialize_document_fields() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let document_date = "2009-03-17T00:00:00Z"
+            .parse::<DateTime<Utc>>()
+            .expect("Failed to parse date");
+        let upload = Upload::new(&path, mime!(Text / Plain))
+            .expect("Failed to create upload")
+            .document_date(document_date)
+            .language("de")
+            .description("Eingescannter Brief");
+
+        let json = serde_json::to_value(DocumentMetadata::from_upload(&upload)).expect("Failed to serialize");
+        let document = &json["metadata"]["document"];
+
+        assert_that(&document["document-date"]).is_equal_to(serde_json::json!("2009-03-17T00:00:00Z"));
+        assert_that(&document["language"]).is_equal_to(serde_json::json!("de"));
+        assert_that(&document["description"]).is_equal_to(serde_json::json!("Eingescannter Brief"));
+        assert_that(&document.get("title")).is_none();
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    client::{AuthorizedClient, GeneralErrHandler, ID},
+    client::{self, AuthorizedClient, GeneralErrHandler, ID},
     errors::{ErrorKind, Result},
 };
 
@@ -9,9 +9,97 @@ use reqwest::{blocking::Response, StatusCode};
 use serde::{self, Deserialize, Serialize};
 use std::string::ToString;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UsersQuery {
-    pub all: bool,
+#[derive(Debug, Serialize, Default)]
+pub struct UsersQuery<'a> {
+    all:    bool,
+    name:   Option<&'a str>,
+    email:  Option<&'a str>,
+    status: Option<UserStatus>,
+    role:   Option<UserRole>,
+    ids:    Option<Vec<&'a str>>,
+    offset: Option<usize>,
+    rows:   Option<usize>,
+}
+
+impl<'a> UsersQuery<'a> {
+    pub fn new() -> Self { Default::default() }
+
+    /// Includes users of all statuses, e.g., blocked users.
+    pub fn all(self) -> UsersQuery<'a> { UsersQuery { all: true, ..self } }
+
+    /// Restricts the query to users whose first or last name contains `name`.
+    pub fn name(self, name: &'a str) -> UsersQuery<'a> {
+        UsersQuery {
+            name: Some(name),
+            ..self
+        }
+    }
+
+    /// Restricts the query to users whose email address contains `email`.
+    pub fn email(self, email: &'a str) -> UsersQuery<'a> {
+        UsersQuery {
+            email: Some(email),
+            ..self
+        }
+    }
+
+    pub fn status(self, status: UserStatus) -> UsersQuery<'a> {
+        UsersQuery {
+            status: Some(status),
+            ..self
+        }
+    }
+
+    pub fn role(self, role: UserRole) -> UsersQuery<'a> {
+        UsersQuery {
+            role: Some(role),
+            ..self
+        }
+    }
+
+    pub fn ids(self, ids: Vec<&'a str>) -> UsersQuery<'a> { UsersQuery { ids: Some(ids), ..self } }
+
+    /// Sets the number of users to skip.
+    pub fn offset(self, offset: usize) -> UsersQuery<'a> {
+        UsersQuery {
+            offset: Some(offset),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of users to return.
+    pub fn rows(self, rows: usize) -> UsersQuery<'a> {
+        UsersQuery {
+            rows: Some(rows),
+            ..self
+        }
+    }
+
+    fn params(self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("all", self.all.to_string())];
+        if let Some(name) = self.name {
+            params.push(("name", name.to_string()));
+        }
+        if let Some(email) = self.email {
+            params.push(("email", email.to_string()));
+        }
+        if let Some(status) = self.status {
+            params.push(("status", status.into()));
+        }
+        if let Some(role) = self.role {
+            params.push(("role", role.into()));
+        }
+        if let Some(ids) = self.ids {
+            params.push(("ids", ids.as_slice().join(",")));
+        }
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+        if let Some(rows) = self.rows {
+            params.push(("rows", rows.to_string()));
+        }
+        params
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,29 +119,144 @@ pub struct User {
     pub role:           UserRole,
     #[serde(rename = "technical-user")]
     pub technical_user: Option<bool>,
+    pub tenant:         Option<ID>,
+    pub organization:   Option<String>,
+    pub language:       Option<String>,
+    pub quota:          Option<Quota>,
+    pub groups:         Option<Vec<ID>>,
 }
 
+/// Storage quota of a user in bytes; users without limit have no `limit`.
 #[derive(PartialEq, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+pub struct Quota {
+    pub used:  u64,
+    pub limit: Option<u64>,
+}
+
+/// Status of a user; statuses unknown to this crate are preserved in `Unknown`.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum UserStatus {
     Invited,
     Pending,
     Active,
     Blocked,
+    Unknown(String),
 }
 
-#[derive(PartialEq, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+impl From<String> for UserStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "invited" => UserStatus::Invited,
+            "pending" => UserStatus::Pending,
+            "active" => UserStatus::Active,
+            "blocked" => UserStatus::Blocked,
+            _ => UserStatus::Unknown(status),
+        }
+    }
+}
+
+impl From<UserStatus> for String {
+    fn from(status: UserStatus) -> Self {
+        match status {
+            UserStatus::Invited => "invited".to_string(),
+            UserStatus::Pending => "pending".to_string(),
+            UserStatus::Active => "active".to_string(),
+            UserStatus::Blocked => "blocked".to_string(),
+            UserStatus::Unknown(status) => status,
+        }
+    }
+}
+
+/// Role of a user; roles unknown to this crate are preserved in `Unknown`.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum UserRole {
     Admin,
     Internal,
     External,
     Guest,
+    Unknown(String),
+}
+
+impl From<String> for UserRole {
+    fn from(role: String) -> Self {
+        match role.as_str() {
+            "admin" => UserRole::Admin,
+            "internal" => UserRole::Internal,
+            "external" => UserRole::External,
+            "guest" => UserRole::Guest,
+            _ => UserRole::Unknown(role),
+        }
+    }
+}
+
+impl From<UserRole> for String {
+    fn from(role: UserRole) -> Self {
+        match role {
+            UserRole::Admin => "admin".to_string(),
+            UserRole::Internal => "internal".to_string(),
+            UserRole::External => "external".to_string(),
+            UserRole::Guest => "guest".to_string(),
+            UserRole::Unknown(role) => role,
+        }
+    }
+}
+
+/// An invitation of a new user by email.
+#[derive(Debug, Serialize)]
+pub struct Invitation<'a> {
+    email:      &'a str,
+    #[serde(rename = "first-name", skip_serializing_if = "Option::is_none")]
+    first_name: Option<&'a str>,
+    #[serde(rename = "last-name", skip_serializing_if = "Option::is_none")]
+    last_name:  Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role:       Option<UserRole>,
+}
+
+impl<'a> Invitation<'a> {
+    pub fn new(email: &'a str) -> Invitation<'a> {
+        Invitation {
+            email,
+            first_name: None,
+            last_name: None,
+            role: None,
+        }
+    }
+
+    pub fn name(self, first_name: &'a str, last_name: &'a str) -> Invitation<'a> {
+        Invitation {
+            first_name: Some(first_name),
+            last_name: Some(last_name),
+            ..self
+        }
+    }
+
+    pub fn role(self, role: UserRole) -> Invitation<'a> {
+        Invitation {
+            role: Some(role),
+            ..self
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct UserAction<'a> {
+    action: &'a str,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct UserUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role:           Option<UserRole>,
+    #[serde(rename = "technical-user", skip_serializing_if = "Option::is_none")]
+    technical_user: Option<bool>,
 }
 
 pub fn search_users(authorized_client: &AuthorizedClient, users_query: UsersQuery) -> Result<UsersResult> {
     let url = format!("https://api.{}/v2/users", authorized_client.base_url);
-    let params = [("all", &users_query.all.to_string())];
+    let params = users_query.params();
 
     let request = authorized_client
         .http_client
@@ -78,3 +281,236 @@ pub fn search_users(authorized_client: &AuthorizedClient, users_query: UsersQuer
 
     Ok(result)
 }
+
+pub fn get_user(authorized_client: &AuthorizedClient, user_id: &str) -> Result<User> {
+    let url = format!("https://api.{}/v2/user/{}", authorized_client.base_url, user_id);
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result)
+}
+
+/// Fetches the user the client is authorized as.
+pub fn current_user(authorized_client: &AuthorizedClient) -> Result<User> {
+    let url = format!("https://api.{}/v2/user/current", authorized_client.base_url);
+
+    let request = authorized_client
+        .http_client
+        .get(&url)
+        .bearer_auth(&authorized_client.token.access_token);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::OK])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result)
+}
+
+/// Invites a new user by email and returns the id of the invited user. This and the other
+/// administration functions fail with `ErrorKind::ApiCallFailedForbidden` unless the caller is an
+/// admin.
+pub fn invite_user(authorized_client: &AuthorizedClient, invitation: Invitation) -> Result<ID> {
+    let url = format!("https://api.{}/v2/users", authorized_client.base_url);
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&invitation);
+    debug!("Request: '{:#?}'", request);
+
+    let response: Response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::CREATED])?;
+    debug!("Response: '{:#?}'", response);
+
+    let status = response.status();
+    let result: client::Id = response.json().map_err(|e| {
+        e.context(ErrorKind::FailedToProcessHttpResponse(
+            status,
+            "reading body".to_string(),
+        ))
+    })?;
+
+    Ok(result.id)
+}
+
+pub fn resend_invitation(authorized_client: &AuthorizedClient, user_id: &str) -> Result<()> {
+    user_action(authorized_client, user_id, "resend-invitation")
+}
+
+pub fn block_user(authorized_client: &AuthorizedClient, user_id: &str) -> Result<()> {
+    user_action(authorized_client, user_id, "block")
+}
+
+pub fn unblock_user(authorized_client: &AuthorizedClient, user_id: &str) -> Result<()> {
+    user_action(authorized_client, user_id, "unblock")
+}
+
+fn user_action(authorized_client: &AuthorizedClient, user_id: &str, action: &str) -> Result<()> {
+    let url = format!("https://api.{}/v2/user/{}", authorized_client.base_url, user_id);
+
+    let request = authorized_client
+        .http_client
+        .post(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(&UserAction { action });
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+pub fn change_user_role(authorized_client: &AuthorizedClient, user_id: &str, role: UserRole) -> Result<()> {
+    let update = UserUpdate {
+        role: Some(role),
+        ..Default::default()
+    };
+    update_user(authorized_client, user_id, &update)
+}
+
+/// Marks a user as technical user, e.g., for integrations, or removes the mark.
+pub fn set_technical_user(authorized_client: &AuthorizedClient, user_id: &str, technical_user: bool) -> Result<()> {
+    let update = UserUpdate {
+        technical_user: Some(technical_user),
+        ..Default::default()
+    };
+    update_user(authorized_client, user_id, &update)
+}
+
+fn update_user(authorized_client: &AuthorizedClient, user_id: &str, update: &UserUpdate) -> Result<()> {
+    let url = format!("https://api.{}/v2/user/{}", authorized_client.base_url, user_id);
+
+    let request = authorized_client
+        .http_client
+        .put(&url)
+        .bearer_auth(&authorized_client.token.access_token)
+        .json(update);
+    debug!("Request: '{:#?}'", request);
+
+    let response = request
+        .send()
+        .map_err(|e| e.context(ErrorKind::HttpRequestFailed))?
+        .general_err_handler(&[StatusCode::NO_CONTENT])?;
+    debug!("Response: '{:#?}'", response);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spectral::prelude::*;
+
+    static USER_JSON: &str = r#"{
+    "id": "a1b2c3",
+    "first-name": "Lukas",
+    "last-name": "Pustina",
+    "email": "lukas@example.com",
+    "status": "active",
+    "role": "admin",
+    "technical-user": false,
+    "tenant": "t1",
+    "organization": "CenterDevice GmbH",
+    "language": "de",
+    "quota": { "used": 1024, "limit": 1048576 },
+    "groups": [ "g1", "g2" ]
+}"#;
+
+    #[test]
+    fn deserialize_user() {
+        let user: User = serde_json::from_str(USER_JSON).expect("Failed to deserialize");
+
+        assert_that(&user.status).is_equal_to(UserStatus::Active);
+        assert_that(&user.role).is_equal_to(UserRole::Admin);
+        assert_that(&user.tenant).is_equal_to(Some("t1".to_string()));
+        assert_that(&user.quota).is_equal_to(Some(Quota {
+            used:  1024,
+            limit: Some(1_048_576),
+        }));
+        assert_that(&user.groups).is_equal_to(Some(vec!["g1".to_string(), "g2".to_string()]));
+    }
+
+    #[test]
+    fn users_query_params() {
+        let query = UsersQuery::new()
+            .email("@example.com")
+            .status(UserStatus::Invited)
+            .role(UserRole::Guest)
+            .rows(20);
+
+        let params = query.params();
+
+        assert_that(&params).is_equal_to(vec![
+            ("all", "false".to_string()),
+            ("email", "@example.com".to_string()),
+            ("status", "invited".to_string()),
+            ("role", "guest".to_string()),
+            ("rows", "20".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn serialize_invitation() {
+        let invitation = Invitation::new("lukas@example.com").role(UserRole::External);
+
+        let json = serde_json::to_value(&invitation).expect("Failed to serialize");
+
+        assert_that(&json).is_equal_to(serde_json::json!({"email": "lukas@example.com", "role": "external"}));
+    }
+
+    #[test]
+    fn roundtrip_unknown_status_and_role() {
+        let json = serde_json::json!({
+            "id": "a1b2c3",
+            "first-name": "Lukas",
+            "last-name": "Pustina",
+            "email": "lukas@example.com",
+            "status": "deactivated",
+            "role": "auditor"
+        });
+
+        let user: User = serde_json::from_value(json).expect("Failed to deserialize");
+        let roundtrip = serde_json::to_value(&user).expect("Failed to serialize");
+
+        assert_that(&user.status).is_equal_to(UserStatus::Unknown("deactivated".to_string()));
+        assert_that(&user.role).is_equal_to(UserRole::Unknown("auditor".to_string()));
+        assert_that(&roundtrip["status"]).is_equal_to(serde_json::json!("deactivated"));
+        assert_that(&roundtrip["role"]).is_equal_to(serde_json::json!("auditor"));
+    }
+}
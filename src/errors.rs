@@ -2,7 +2,7 @@ use crate::ID;
 
 use failure::{Backtrace, Context, Fail};
 use reqwest::StatusCode;
-use std::fmt;
+use std::{fmt, path::PathBuf};
 
 /// The error kind for errors that get returned in the crate
 #[derive(Eq, PartialEq, Debug, Fail)]
@@ -20,12 +20,35 @@ pub enum ErrorKind {
     ApiCallFailedInvalidToken(StatusCode),
     #[fail(display = "API call failed because of too many reqwests, status code = {}", _0)]
     ApiCallFailedTooManyRequests(StatusCode),
+    #[fail(display = "API call failed because of insufficient rights, status code = {}", _0)]
+    ApiCallFailedForbidden(StatusCode),
 
     #[fail(display = "API call failed with status code = {}, '{}'", _0, _1)]
     ApiCallFailed(StatusCode, String),
 
     #[fail(display = "failed documents; ids='{:?}'", _0)]
     FailedDocuments(Vec<ID>),
+
+    #[fail(display = "file already exists; path={:?}", _0)]
+    FileAlreadyExists(PathBuf),
+
+    #[fail(display = "downloaded size does not match; expected={}, actual={}", _0, _1)]
+    DownloadSizeMismatch(u64, u64),
+
+    #[fail(display = "downloaded checksum does not match; expected={}, actual={}", _0, _1)]
+    DownloadChecksumMismatch(String, String),
+
+    #[fail(display = "failed to spawn worker thread")]
+    FailedToSpawnWorker,
+
+    #[fail(display = "failed to process extended metadata, '{}'", _0)]
+    FailedToProcessExtendedMetadata(String),
+
+    #[fail(display = "invalid range; {}", _0)]
+    InvalidRange(String),
+
+    #[fail(display = "invalid permission; {}", _0)]
+    InvalidPermission(String),
 }
 
 impl Clone for ErrorKind {
@@ -33,14 +56,24 @@ impl Clone for ErrorKind {
         use self::ErrorKind::*;
         match *self {
             HttpRequestFailed => HttpRequestFailed,
+            FailedToSpawnWorker => FailedToSpawnWorker,
             ApiCallFailed(ref status_code, ref body) => ApiCallFailed(*status_code, body.clone()),
             ApiCallFailedInvalidToken(ref status_code) => ApiCallFailedInvalidToken(*status_code),
             ApiCallFailedTooManyRequests(ref status_code) => ApiCallFailedTooManyRequests(*status_code),
+            ApiCallFailedForbidden(ref status_code) => ApiCallFailedForbidden(*status_code),
             FailedToProcessHttpResponse(ref status_code, ref body) => {
                 FailedToProcessHttpResponse(*status_code, body.clone())
             }
             FailedToPrepareHttpRequest(ref s) => FailedToPrepareHttpRequest(s.clone()),
+            FailedToProcessExtendedMetadata(ref s) => FailedToProcessExtendedMetadata(s.clone()),
+            InvalidRange(ref s) => InvalidRange(s.clone()),
+            InvalidPermission(ref s) => InvalidPermission(s.clone()),
             FailedDocuments(ref s) => FailedDocuments(s.clone()),
+            FileAlreadyExists(ref p) => FileAlreadyExists(p.clone()),
+            DownloadSizeMismatch(expected, actual) => DownloadSizeMismatch(expected, actual),
+            DownloadChecksumMismatch(ref expected, ref actual) => {
+                DownloadChecksumMismatch(expected.clone(), actual.clone())
+            }
         }
     }
 }
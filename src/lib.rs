@@ -7,11 +7,15 @@ pub use reqwest::{blocking::Client as HttpClient, Certificate};
 
 use crate::{
     client::{
-        collections::{CollectionsQuery, CollectionsResult},
-        download::Download,
-        search::{Search, SearchResult},
+        collections::{Collection, CollectionOptions, CollectionsQuery, CollectionsResult},
+        download::{BulkDownload, BulkDownloadResult, Download},
+        folders::{Folder, FoldersResult},
+        groups::{Group, GroupsResult},
+        saved_searches::SavedSearchesResult,
+        search::{Search, SearchDocumentsIter, SearchResult},
+        sharing::{MembersResult, Permission, Principal, Shares},
         upload::Upload,
-        users::{UsersQuery, UsersResult},
+        users::{Invitation, User, UserRole, UsersQuery, UsersResult},
         AuthorizedClient,
         UnauthorizedClient,
         ID,
@@ -19,17 +23,86 @@ use crate::{
     errors::Result,
 };
 
+use serde::Serialize;
+
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 pub trait CenterDevice {
     fn refresh_access_token(&self) -> Result<Token>;
     fn search_documents(&self, search: Search) -> Result<SearchResult>;
+    fn search_documents_iter<'a>(&'a self, search: Search<'a>) -> SearchDocumentsIter<'a, Self>
+    where
+        Self: Sized;
+    fn list_saved_searches(&self) -> Result<SavedSearchesResult>;
+    fn create_saved_search(&self, name: &str, search: &Search) -> Result<ID>;
+    fn run_saved_search(&self, saved_search_id: &str) -> Result<SearchResult>;
+    fn update_saved_search(&self, saved_search_id: &str, name: &str, search: &Search) -> Result<()>;
+    fn delete_saved_search(&self, saved_search_id: &str) -> Result<()>;
     fn upload_file(&self, upload: Upload) -> Result<ID>;
     fn download_file(&self, download: Download) -> Result<u64>;
     fn download_file_with_progress<T: WithProgress>(&self, download: Download, progress: &mut T) -> Result<u64>;
+    fn download_many(&self, bulk_download: BulkDownload) -> Result<BulkDownloadResult>;
+    fn download_many_with_progress<T: WithProgress>(
+        &self,
+        bulk_download: BulkDownload,
+        progress: &mut T,
+    ) -> Result<BulkDownloadResult>;
     fn delete_documents(&self, document_ids: &[&str]) -> Result<()>;
+    fn update_extended_metadata<T: Serialize>(&self, document_id: &str, patch: &T) -> Result<()>;
+    fn share_documents(&self, document_ids: &[&str], principals: &[Principal]) -> Result<()>;
+    fn unshare_documents(&self, document_ids: &[&str], principals: &[Principal]) -> Result<()>;
+    fn document_shares(&self, document_id: &str) -> Result<Shares>;
     fn search_users(&self, users_query: UsersQuery) -> Result<UsersResult>;
+    fn get_user(&self, user_id: &str) -> Result<User>;
+    fn current_user(&self) -> Result<User>;
+    fn invite_user(&self, invitation: Invitation) -> Result<ID>;
+    fn resend_invitation(&self, user_id: &str) -> Result<()>;
+    fn block_user(&self, user_id: &str) -> Result<()>;
+    fn unblock_user(&self, user_id: &str) -> Result<()>;
+    fn change_user_role(&self, user_id: &str, role: UserRole) -> Result<()>;
+    fn set_technical_user(&self, user_id: &str, technical_user: bool) -> Result<()>;
+    fn list_groups(&self) -> Result<GroupsResult>;
+    fn get_group(&self, group_id: &str) -> Result<Group>;
+    fn create_group(&self, name: &str, user_ids: &[&str]) -> Result<Group>;
+    fn rename_group(&self, group_id: &str, name: &str) -> Result<()>;
+    fn delete_group(&self, group_id: &str) -> Result<()>;
+    fn add_group_members(&self, group_id: &str, user_ids: &[&str]) -> Result<()>;
+    fn remove_group_members(&self, group_id: &str, user_ids: &[&str]) -> Result<()>;
     fn search_collections(&self, collections_query: CollectionsQuery) -> Result<CollectionsResult>;
+    fn collection_documents<'a>(
+        &'a self,
+        collection_id: &'a str,
+        folder_id: Option<&'a str>,
+    ) -> SearchDocumentsIter<'a, Self>
+    where
+        Self: Sized;
+    fn get_collection(&self, collection_id: &str) -> Result<Collection>;
+    fn create_collection(&self, name: &str, options: CollectionOptions) -> Result<Collection>;
+    fn rename_collection(&self, collection_id: &str, name: &str) -> Result<Collection>;
+    fn archive_collection(&self, collection_id: &str) -> Result<Collection>;
+    fn unarchive_collection(&self, collection_id: &str) -> Result<Collection>;
+    fn set_collection_public(&self, collection_id: &str, public: bool) -> Result<Collection>;
+    fn set_collection_auditing(&self, collection_id: &str, auditing: bool) -> Result<Collection>;
+    fn delete_collection(&self, collection_id: &str) -> Result<()>;
+    fn collection_members(&self, collection_id: &str) -> Result<MembersResult>;
+    fn add_collection_member(&self, collection_id: &str, principal: Principal, permission: Permission) -> Result<()>;
+    fn set_collection_member_permission(
+        &self,
+        collection_id: &str,
+        principal: Principal,
+        permission: Permission,
+    ) -> Result<()>;
+    fn remove_collection_member(&self, collection_id: &str, principal: Principal) -> Result<()>;
+    fn folder_tree(&self, collection_id: &str) -> Result<FoldersResult>;
+    fn get_folder(&self, folder_id: &str) -> Result<Folder>;
+    fn create_folder(&self, name: &str, collection_id: &str, parent_id: Option<&str>) -> Result<Folder>;
+    fn rename_folder(&self, folder_id: &str, name: &str) -> Result<()>;
+    fn move_folder(&self, folder_id: &str, parent_id: Option<&str>) -> Result<()>;
+    fn delete_folder(&self, folder_id: &str) -> Result<()>;
+    fn folder_documents<'a>(&'a self, folder_id: &'a str) -> SearchDocumentsIter<'a, Self>
+    where
+        Self: Sized;
+    fn move_documents(&self, document_ids: &[&str], source_folder_id: &str, target_folder_id: &str) -> Result<()>;
 }
 
 pub struct ClientBuilder<'a> {
@@ -120,7 +193,8 @@ impl<'a> ClientCredentials<'a> {
 }
 
 pub trait WithProgress {
-    fn setup(&mut self, size: usize);
+    /// Called before the transfer starts; `size` is `None` if the total size is unknown.
+    fn setup(&mut self, size: Option<u64>);
     fn progress(&mut self, amount: usize);
     fn finish(&self);
 }
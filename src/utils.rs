@@ -1,5 +1,5 @@
 pub(crate) mod serialize {
-    use serde::Serializer;
+    use serde::{Serialize, Serializer};
 
     pub(crate) fn mime_type<S>(mime_type: &mime::Mime, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
     where
@@ -8,11 +8,30 @@ pub(crate) mod serialize {
         let s = mime_type.to_string();
         serializer.serialize_str(&s)
     }
+
+    pub(crate) fn option_mime_types<S>(
+        mime_types: &Option<Vec<mime::Mime>>,
+        serializer: S,
+    ) -> ::std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let strings: Option<Vec<String>> = mime_types.as_ref().map(|m| m.iter().map(ToString::to_string).collect());
+        strings.serialize(serializer)
+    }
+
+    /// Returns the serialized name of a unit variant, e.g., to use it as query parameter.
+    pub(crate) fn variant_name<T: Serialize>(variant: &T) -> String {
+        match serde_json::to_value(variant) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => panic!("Unit variants must serialize to strings"),
+        }
+    }
 }
 
 pub(crate) mod deserialize {
     use core::fmt;
-    use serde::{de::Visitor, Deserializer};
+    use serde::{de::Visitor, Deserialize, Deserializer};
     use std::str::FromStr;
 
     pub(crate) fn mime_type<'de, D>(deserializer: D) -> ::std::result::Result<mime::Mime, D::Error>
@@ -38,4 +57,18 @@ pub(crate) mod deserialize {
 
         deserializer.deserialize_string(MimeVisitor)
     }
+
+    pub(crate) fn option_mime_types<'de, D>(deserializer: D) -> ::std::result::Result<Option<Vec<mime::Mime>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let strings: Option<Vec<String>> = Option::deserialize(deserializer)?;
+        strings
+            .map(|s| {
+                s.iter()
+                    .map(|m| mime::Mime::from_str(m).map_err(|_| serde::de::Error::custom("invalid mime type")))
+                    .collect()
+            })
+            .transpose()
+    }
 }